@@ -0,0 +1,25 @@
+use std::env;
+use std::process::Command;
+
+fn main() {
+    // Recorded for `color_eyre::environment::Environment`, as the compiler and
+    // target color-eyre is built with are the ones the application is built
+    // with.
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|version| version.trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    println!("cargo:rustc-env=COLOR_EYRE_RUSTC_VERSION={}", version);
+    println!(
+        "cargo:rustc-env=COLOR_EYRE_TARGET={}",
+        env::var("TARGET").unwrap_or_else(|_| "unknown".to_string())
+    );
+    println!("cargo:rerun-if-env-changed=RUSTC");
+    println!("cargo:rerun-if-changed=build.rs");
+}
@@ -12,7 +12,7 @@ fn main() -> Result<(), Report> {
     color_eyre::config::HookBuilder::default()
         .issue_url(concat!(env!("CARGO_PKG_REPOSITORY"), "/issues/new"))
         .add_issue_metadata("version", env!("CARGO_PKG_VERSION"))
-        .issue_filter(|kind| match kind {
+        .issue_filter(|context| match context.kind() {
             color_eyre::ErrorKind::NonRecoverable(_) => false,
             color_eyre::ErrorKind::Recoverable(_) => true,
         })
@@ -1,24 +1,20 @@
-use color_eyre::{eyre::eyre, eyre::Report, Section};
+use color_eyre::config::{HookBuilder, ReportLayout};
+use color_eyre::eyre::Report;
+use color_eyre::section::{Aggregate, CollectErrors};
 use thiserror::Error;
 
 fn main() -> Result<(), Report> {
-    color_eyre::install()?;
+    HookBuilder::default()
+        .report_layout(ReportLayout::new().error_tree(true))
+        .install()?;
     let errors = get_errors();
     join_errors(errors)
 }
 
 fn join_errors(results: Vec<Result<(), SourceError>>) -> Result<(), Report> {
-    if results.iter().all(|r| r.is_ok()) {
-        return Ok(());
-    }
-
     results
         .into_iter()
-        .filter(Result::is_err)
-        .map(Result::unwrap_err)
-        .fold(Err(eyre!("encountered multiple errors")), |report, e| {
-            report.error(e)
-        })
+        .collect_errors(Aggregate::new("encountered multiple errors").limit(10))
 }
 
 /// Helper function to generate errors
@@ -0,0 +1,29 @@
+#![allow(dead_code, unused_imports)]
+use color_eyre::eyre::{eyre, Report, WrapErr};
+
+/// Usage: symbolize <report-file> <unstripped-binary-or-debuginfo>...
+#[cfg(feature = "symbolize")]
+fn main() -> Result<(), Report> {
+    use color_eyre::symbolize::{RawBacktrace, Symbolizer};
+
+    color_eyre::install()?;
+
+    let mut args = std::env::args().skip(1);
+    let report = args
+        .next()
+        .ok_or_else(|| eyre!("expected the path of an error report containing a raw backtrace"))?;
+    let report =
+        std::fs::read_to_string(&report).wrap_err_with(|| format!("failed to read {}", report))?;
+    let raw: RawBacktrace = report.parse()?;
+
+    let symbolizer = args.try_fold(Symbolizer::new(), Symbolizer::add_object)?;
+
+    println!("{}", symbolizer.symbolize(&raw));
+
+    Ok(())
+}
+
+#[cfg(not(feature = "symbolize"))]
+fn main() {
+    unimplemented!("this example requires the \"symbolize\" feature")
+}
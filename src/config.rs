@@ -1,7 +1,9 @@
 //! Configuration options for customizing the behavior of the provided panic
 //! and error reporting hooks
+pub use crate::redact::{RedactCallback, Redaction};
 use crate::{
-    section::PanicMessage,
+    redact::{Redacted, Redactor},
+    section::{DefinedKind, PanicMessage, SectionKind},
     writers::{EnvSection, WriterExt},
 };
 use fmt::Display;
@@ -9,7 +11,11 @@ use indenter::{indented, Format};
 use owo_colors::{style, OwoColorize, Style};
 use std::env;
 use std::fmt::Write as _;
-use std::{fmt, path::PathBuf, sync::Arc};
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 #[derive(Debug)]
 struct InstallError;
@@ -65,6 +71,8 @@ pub struct Theme {
     pub(crate) panic_file: Style,
     pub(crate) panic_line_number: Style,
     pub(crate) hidden_frames: Style,
+    pub(crate) redundant_error: Style,
+    pub(crate) defined_kinds: bool,
 }
 
 macro_rules! theme_setters {
@@ -104,8 +112,10 @@ impl Theme {
             panic_file: style().purple(),
             panic_line_number: style().purple(),
             hidden_frames: style().bright_cyan(),
+            redundant_error: style().bright_black(),
             spantrace_target: style().bright_red(),
             spantrace_fields: style().bright_cyan(),
+            defined_kinds: true,
         }
     }
 
@@ -132,6 +142,8 @@ impl Theme {
             panic_file: style().purple(),
             panic_line_number: style().purple(),
             hidden_frames: style().blue(),
+            redundant_error: style().bright_black(),
+            defined_kinds: true,
         }
     }
 
@@ -173,6 +185,15 @@ impl Theme {
         panic_line_number,
         /// Styles the "N frames hidden" message
         hidden_frames,
+        /// Styles errors of the chain that repeat their parent, see `RedundantErrors::Dim`
+        redundant_error,
+    }
+
+    /// Whether labels of `DefinedKind`s are printed with their own style.
+    /// Enabled by the dark and light themes, disabled by a blank theme
+    pub fn defined_kinds(mut self, styled: bool) -> Self {
+        self.defined_kinds = styled;
+        self
     }
 }
 
@@ -180,7 +201,7 @@ impl Theme {
 #[derive(Debug)]
 #[non_exhaustive]
 pub struct Frame {
-    /// Frame index
+    /// Frame index, shared by the physical frame and the symbols inlined into it
     pub n: usize,
     /// frame symbol name
     pub name: Option<String>,
@@ -188,40 +209,53 @@ pub struct Frame {
     pub lineno: Option<u32>,
     /// source file path
     pub filename: Option<PathBuf>,
+    /// instruction pointer of the physical frame this symbol was resolved from
+    pub ip: Option<usize>,
+    /// path of the executable or shared object containing `ip`
+    pub module: Option<PathBuf>,
+    /// address `module` was loaded at
+    pub module_base: Option<usize>,
+    /// whether this symbol was inlined into physical frame `n`, which precedes it
+    pub inlined: bool,
+}
+
+/// Split a symbol name into the function name and its hash suffix, if any
+pub(crate) fn split_hash(name: &str) -> (&str, Option<&str>) {
+    // Does the function have a hash suffix?
+    // (dodging a dep on the regex crate here)
+    let has_hash_suffix = name.len() > 19
+        && &name[name.len() - 19..name.len() - 16] == "::h"
+        && name[name.len() - 16..].chars().all(|x| x.is_digit(16));
+
+    if has_hash_suffix {
+        (&name[..name.len() - 19], Some(&name[name.len() - 19..]))
+    } else {
+        (name, None)
+    }
 }
 
 #[derive(Debug)]
-struct StyledFrame<'a>(&'a Frame, Theme);
+struct StyledFrame<'a>(&'a Frame, Theme, Option<&'a SpanFrame>, bool);
 
 impl<'a> fmt::Display for StyledFrame<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let Self(frame, theme) = self;
+        let Self(frame, theme, span, nested) = self;
 
         let is_dependency_code = frame.is_dependency_code();
 
-        // Print frame index.
-        write!(f, "{:>2}: ", frame.n)?;
-
-        // Does the function have a hash suffix?
-        // (dodging a dep on the regex crate here)
-        let name = frame.name.as_deref().unwrap_or("<unknown>");
-        let has_hash_suffix = name.len() > 19
-            && &name[name.len() - 19..name.len() - 16] == "::h"
-            && name[name.len() - 16..].chars().all(|x| x.is_digit(16));
-
-        let hash_suffix = if has_hash_suffix {
-            &name[name.len() - 19..]
+        // Print frame index, or indent symbols inlined into the frame above.
+        let indent = if *nested {
+            write!(f, "      ")?;
+            "  "
         } else {
-            "<unknown>"
+            write!(f, "{:>2}: ", frame.n)?;
+            ""
         };
 
-        // Print function name.
-        let name = if has_hash_suffix {
-            &name[..name.len() - 19]
-        } else {
-            name
-        };
+        let (name, hash_suffix) = split_hash(frame.name.as_deref().unwrap_or("<unknown>"));
+        let hash_suffix = hash_suffix.unwrap_or("<unknown>");
 
+        // Print function name.
         if is_dependency_code {
             write!(f, "{}", (name).style(theme.dependency_code))?;
         } else {
@@ -230,6 +264,13 @@ impl<'a> fmt::Display for StyledFrame<'a> {
 
         write!(f, "{}", (hash_suffix).style(theme.code_hash))?;
 
+        // Print the fields of a span entered in this frame.
+        if let Some(span) = span {
+            if !span.fields.is_empty() {
+                write!(f, " with {}", span.fields.style(theme.spantrace_fields))?;
+            }
+        }
+
         let mut separated = f.header("\n");
 
         // Print source location, if known.
@@ -244,7 +285,8 @@ impl<'a> fmt::Display for StyledFrame<'a> {
             .map_or("<unknown line>".to_owned(), |x| x.to_string());
         write!(
             &mut separated.ready(),
-            "    at {}:{}",
+            "{}    at {}:{}",
+            indent,
             file.style(theme.file),
             lineno.style(theme.line_number),
         )?;
@@ -255,22 +297,54 @@ impl<'a> fmt::Display for StyledFrame<'a> {
             lib_verbosity()
         };
 
-        // Maybe print source.
+        // Maybe print module and source.
+        if v >= Verbosity::Full && !nested {
+            write!(&mut separated.ready(), "{}", ModuleSection(frame, *theme))?;
+        }
+
         if v >= Verbosity::Full {
-            write!(&mut separated.ready(), "{}", SourceSection(&frame, *theme))?;
+            write!(
+                &mut separated.ready(),
+                "{}",
+                SourceSection(frame.filename.as_deref(), frame.lineno, *theme)
+            )?;
         }
 
         Ok(())
     }
 }
 
-struct SourceSection<'a>(&'a Frame, Theme);
+struct ModuleSection<'a>(&'a Frame, Theme);
 
-impl fmt::Display for SourceSection<'_> {
+impl fmt::Display for ModuleSection<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let Self(frame, theme) = self;
 
-        let (lineno, filename) = match (frame.lineno, frame.filename.as_ref()) {
+        let (module, ip) = match (frame.module.as_ref(), frame.ip) {
+            (Some(module), Some(ip)) => (module, ip),
+            _ => return Ok(()),
+        };
+
+        write!(f, "    in {}", module.display().style(theme.file))?;
+
+        match frame.module_base {
+            Some(base) => write!(
+                f,
+                " {}",
+                format_args!("+{:#x}", ip.wrapping_sub(base)).style(theme.code_hash)
+            ),
+            None => write!(f, " {}", format_args!("@ {:#x}", ip).style(theme.code_hash)),
+        }
+    }
+}
+
+struct SourceSection<'a>(Option<&'a Path>, Option<u32>, Theme);
+
+impl fmt::Display for SourceSection<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self(filename, lineno, theme) = self;
+
+        let (lineno, filename) = match (*lineno, *filename) {
             (Some(a), Some(b)) => (a, b),
             // Without a line number and file name, we can't sensibly proceed.
             _ => return Ok(()),
@@ -312,7 +386,50 @@ impl fmt::Display for SourceSection<'_> {
 }
 
 impl Frame {
-    fn is_dependency_code(&self) -> bool {
+    /// Resolve the frames of a backtrace, one `Frame` per symbol.
+    ///
+    /// A physical frame with inlined functions resolves to several symbols
+    /// that share its index. The symbol of the physical frame comes first,
+    /// followed by the symbols inlined into it marked as `inlined`.
+    pub(crate) fn collect(trace: &backtrace::Backtrace) -> Vec<Frame> {
+        let mut frames = Vec::new();
+
+        for (i, frame) in trace.frames().iter().enumerate() {
+            let ip = frame.ip() as usize;
+            let module = crate::module::Module::containing(ip);
+            let module_base = module
+                .as_ref()
+                .map(|module| module.base)
+                .or_else(|| frame.module_base_address().map(|base| base as usize));
+            let symbols = frame.symbols();
+
+            let new_frame = |symbol: Option<&backtrace::BacktraceSymbol>, inlined| Frame {
+                n: i + 1,
+                name: symbol.and_then(|sym| sym.name()).map(|x| x.to_string()),
+                lineno: symbol.and_then(|sym| sym.lineno()),
+                filename: symbol.and_then(|sym| sym.filename()).map(|x| x.into()),
+                ip: Some(ip),
+                module: module.as_ref().map(|module| module.path.clone()),
+                module_base,
+                inlined,
+            };
+
+            // Keep frames that couldn't be resolved so their address isn't
+            // lost. Otherwise the last symbol is the physical frame, and the
+            // ones before it were inlined into it.
+            match symbols.split_last() {
+                None => frames.push(new_frame(None, false)),
+                Some((physical, inlined)) => {
+                    frames.push(new_frame(Some(physical), false));
+                    frames.extend(inlined.iter().map(|sym| new_frame(Some(sym), true)));
+                }
+            }
+        }
+
+        frames
+    }
+
+    pub(crate) fn is_dependency_code(&self) -> bool {
         const SYM_PREFIXES: &[&str] = &[
             "std::",
             "core::",
@@ -413,20 +530,657 @@ impl Frame {
     }
 }
 
+/// How the `SpanTrace` and `Backtrace` of a report are laid out
+///
+/// # Details
+///
+/// Functions annotated with `#[instrument]` show up in both the span trace and
+/// the backtrace. The merged layouts combine both into a single execution
+/// trace, placing each span next to the backtrace frame of the function that
+/// entered it. Spans are matched to frames by function name and source file.
+/// Spans without a matching frame are placed before the frame of the next
+/// matched span.
+///
+/// # Examples
+///
+/// ```rust
+/// use color_eyre::config::{HookBuilder, TraceLayout};
+///
+/// HookBuilder::default()
+///     .trace_layout(TraceLayout::Deduplicated)
+///     .install()
+///     .unwrap();
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum TraceLayout {
+    /// Print the span trace and the backtrace as separate sections. This is
+    /// the default
+    #[default]
+    Separate,
+    /// Print a single execution trace with every span listed right before the
+    /// frame of the function it was entered in
+    Interleaved,
+    /// Like `Interleaved`, but spans that match a frame are folded into that
+    /// frame, showing their fields next to the frame's location
+    Deduplicated,
+}
+
+/// A part of an error report that can be ordered or hidden with a
+/// `ReportLayout`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ReportPart {
+    /// The location the report was created at
+    Location,
+    /// The sections of the given kind attached to the report
+    Section(SectionKind),
+    /// The captured `SpanTrace`
+    SpanTrace,
+    /// The captured `Backtrace`
+    Backtrace,
+}
+
+/// How the parts of an error report are ordered, grouped and hidden
+///
+/// # Details
+///
+/// Parts are printed from the highest to the lowest priority. Sections of
+/// different kinds that share a priority are printed together in the order
+/// they were attached. The default priorities are:
+///
+/// | Part                          | Priority |
+/// |-------------------------------|----------|
+/// | `Location`                    | 90       |
+/// | `Section(SectionKind::Error)` | 80       |
+/// | `Section(SectionKind::Field)` | 70       |
+/// | `Section(SectionKind::Custom)`| 60       |
+/// | `SpanTrace`                   | 50       |
+/// | `Backtrace`                   | 40       |
+/// | Notes, warnings, suggestions  | 30       |
+///
+/// Sections of a `DefinedKind` use the priority of their kind unless the
+/// layout sets one. The fingerprint, the environment section and the issue
+/// link always come
+/// last. The layout only affects the printed report, generated issues and
+/// crash reports include every section.
+///
+/// # Examples
+///
+/// Print suggestions above the backtrace, each kind under a single header,
+/// and never print notes:
+///
+/// ```rust
+/// use color_eyre::config::{HookBuilder, ReportLayout, ReportPart};
+/// use color_eyre::section::SectionKind;
+///
+/// HookBuilder::default()
+///     .report_layout(
+///         ReportLayout::new()
+///             .priority(ReportPart::Section(SectionKind::Suggestion), 55)
+///             .hide(ReportPart::Section(SectionKind::Note))
+///             .group_sections(true),
+///     )
+///     .install()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportLayout {
+    priorities: Vec<(ReportPart, i32)>,
+    hidden: Vec<ReportPart>,
+    group_sections: bool,
+    deduplicate_sections: bool,
+    redundant_errors: RedundantErrors,
+    error_tree: bool,
+    error_backtraces: bool,
+}
+
+impl ReportLayout {
+    /// Construct the default layout
+    pub fn new() -> Self {
+        Self {
+            priorities: vec![
+                (ReportPart::Location, 90),
+                (ReportPart::Section(SectionKind::Error), 80),
+                (ReportPart::Section(SectionKind::Field), 70),
+                (ReportPart::Section(SectionKind::Custom), 60),
+                (ReportPart::SpanTrace, 50),
+                (ReportPart::Backtrace, 40),
+                (ReportPart::Section(SectionKind::Note), 30),
+                (ReportPart::Section(SectionKind::Warning), 30),
+                (ReportPart::Section(SectionKind::Suggestion), 30),
+            ],
+            hidden: Vec::new(),
+            group_sections: false,
+            deduplicate_sections: false,
+            redundant_errors: RedundantErrors::Show,
+            error_tree: false,
+            error_backtraces: false,
+        }
+    }
+
+    /// Set the priority of a part, higher priorities are printed first
+    pub fn priority(mut self, part: ReportPart, priority: i32) -> Self {
+        match self.priorities.iter_mut().find(|(p, _)| *p == part) {
+            Some((_, p)) => *p = priority,
+            None => self.priorities.push((part, priority)),
+        }
+        self
+    }
+
+    /// Never print the given part
+    pub fn hide(mut self, part: ReportPart) -> Self {
+        if !self.hidden.contains(&part) {
+            self.hidden.push(part);
+        }
+        self
+    }
+
+    /// Print notes, warnings and suggestions as one list per kind under a
+    /// single header, like `Suggestions:`, instead of labeling every section
+    pub fn group_sections(mut self, group: bool) -> Self {
+        self.group_sections = group;
+        self
+    }
+
+    /// Print sections that were attached more than once with the same kind
+    /// and text only once, followed by a counter like `(×3)`
+    pub fn deduplicate_sections(mut self, deduplicate: bool) -> Self {
+        self.deduplicate_sections = deduplicate;
+        self
+    }
+
+    /// Configures how errors of the chain whose message is contained in the
+    /// message of the error wrapping them are printed
+    pub fn redundant_errors(mut self, errors: RedundantErrors) -> Self {
+        self.redundant_errors = errors;
+        self
+    }
+
+    /// Print the errors attached with `Section::error` as a numbered tree
+    /// under a single `Errors:` header, with the sources of each error and
+    /// the location it was attached at
+    pub fn error_tree(mut self, tree: bool) -> Self {
+        self.error_tree = tree;
+        self
+    }
+
+    /// Capture a backtrace for every error attached with `Section::error`,
+    /// printed under the error in the tree, see `error_tree`
+    ///
+    /// Backtraces are only captured while backtraces are enabled for error
+    /// reports, see `RUST_LIB_BACKTRACE`.
+    pub fn error_backtraces(mut self, backtraces: bool) -> Self {
+        self.error_backtraces = backtraces;
+        self
+    }
+
+    pub(crate) fn is_grouped(&self) -> bool {
+        self.group_sections
+    }
+
+    pub(crate) fn is_error_tree(&self) -> bool {
+        self.error_tree
+    }
+
+    pub(crate) fn has_error_backtraces(&self) -> bool {
+        self.error_tree && self.error_backtraces
+    }
+
+    pub(crate) fn is_deduplicated(&self) -> bool {
+        self.deduplicate_sections
+    }
+
+    pub(crate) fn redundant_errors_mode(&self) -> RedundantErrors {
+        self.redundant_errors
+    }
+
+    /// The visible parts, grouped by priority from the highest to the lowest
+    ///
+    /// `defined` are the kinds of the defined sections attached to the
+    /// report, placed by their own priority unless the layout sets one.
+    pub(crate) fn groups(&self, defined: &[DefinedKind]) -> Vec<Vec<ReportPart>> {
+        let mut parts: Vec<_> = self.priorities.clone();
+        for kind in defined {
+            let part = ReportPart::Section(SectionKind::Defined(kind.label));
+            if !parts.iter().any(|(p, _)| *p == part) {
+                parts.push((part, kind.priority));
+            }
+        }
+        parts.retain(|(part, _)| !self.hidden.contains(part));
+        parts.sort_by_key(|(_, priority)| std::cmp::Reverse(*priority));
+
+        let mut groups: Vec<(i32, Vec<ReportPart>)> = Vec::new();
+        for (part, priority) in parts {
+            match groups.last_mut() {
+                Some((p, group)) if *p == priority => group.push(part),
+                _ => groups.push((priority, vec![part])),
+            }
+        }
+
+        groups.into_iter().map(|(_, group)| group).collect()
+    }
+}
+
+impl Default for ReportLayout {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How errors of a chain that repeat the message of their parent are printed
+///
+/// Errors wrapped with `WrapErr` often include the message of their source,
+/// as in `failed to read config: No such file or directory` followed by
+/// `No such file or directory`. An error is redundant when its message is
+/// contained in the message of the error right above it in the chain.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RedundantErrors {
+    /// Print redundant errors like any other error. This is the default
+    #[default]
+    Show,
+    /// Print redundant errors with the `redundant_error` style of the theme
+    Dim,
+    /// Leave redundant errors out of the chain
+    Elide,
+}
+
+/// An entry of a `SpanTrace`, as placed in an execution trace
+#[derive(Debug)]
+pub(crate) struct SpanFrame {
+    pub(crate) target: &'static str,
+    pub(crate) name: &'static str,
+    pub(crate) fields: String,
+    pub(crate) file: Option<&'static str>,
+    pub(crate) line: Option<u32>,
+    /// the names of the fields the span was declared with
+    #[cfg(feature = "capture-spantrace")]
+    field_names: Vec<&'static str>,
+}
+
+impl SpanFrame {
+    #[cfg(feature = "capture-spantrace")]
+    pub(crate) fn collect(span_trace: &tracing_error::SpanTrace) -> Vec<SpanFrame> {
+        let mut spans = Vec::new();
+
+        span_trace.with_spans(|metadata, fields| {
+            spans.push(SpanFrame {
+                target: metadata.target(),
+                name: metadata.name(),
+                fields: fields.to_string(),
+                file: metadata.file(),
+                line: metadata.line(),
+                field_names: metadata.fields().iter().map(|field| field.name()).collect(),
+            });
+            true
+        });
+
+        spans
+    }
+
+    /// Whether `frame` is a frame of the function this span was entered in
+    fn matches(&self, frame: &Frame) -> bool {
+        let name = match frame.name.as_deref() {
+            Some(name) => split_hash(name).0,
+            None => return false,
+        };
+
+        let mut function = name;
+        while let Some(outer) = function.strip_suffix("::{{closure}}") {
+            function = outer;
+        }
+
+        let name_matches = matches!(function.strip_suffix(self.name), Some(path) if path.ends_with("::"))
+            && name.contains(self.target);
+
+        let file_matches = match (self.file, frame.filename.as_ref()) {
+            (Some(file), Some(filename)) => filename.ends_with(file),
+            _ => true,
+        };
+
+        name_matches && file_matches
+    }
+}
+
+struct StyledSpan<'a>(&'a SpanFrame, Theme);
+
+impl fmt::Display for StyledSpan<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self(span, theme) = self;
+
+        write!(
+            f,
+            "{}",
+            format_args!("{}::{}", span.target, span.name).style(theme.spantrace_target)
+        )?;
+
+        if !span.fields.is_empty() {
+            write!(f, " with {}", span.fields.style(theme.spantrace_fields))?;
+        }
+
+        match span.file {
+            Some(file) => {
+                let lineno = span
+                    .line
+                    .map_or("<unknown line>".to_owned(), |x| x.to_string());
+                write!(
+                    f,
+                    "\n    at {}:{}",
+                    file.style(theme.file),
+                    lineno.style(theme.line_number)
+                )
+            }
+            None => write!(f, "\n    at <unknown source file>"),
+        }
+    }
+}
+
+/// What to do with the span fields matched by a rule
+///
+/// See `HookBuilder::span_field_rule`.
+#[cfg(feature = "capture-spantrace")]
+#[cfg_attr(docsrs, doc(cfg(feature = "capture-spantrace")))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SpanFieldAction {
+    /// Leave the field out entirely
+    Drop,
+    /// Shorten the value of the field to at most this many characters, not
+    /// counting the quotes around string values
+    Truncate(usize),
+    /// Replace the value of the field with `[redacted]`
+    Redact,
+}
+
+/// Rules applied to the spans of a `SpanTrace` before they are printed
+#[cfg(feature = "capture-spantrace")]
+#[derive(Debug, Default)]
+pub(crate) struct SpanFilter {
+    rules: Vec<(String, SpanFieldAction)>,
+    limit: Option<usize>,
+}
+
+#[cfg(feature = "capture-spantrace")]
+impl SpanFilter {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.rules.is_empty() && self.limit.is_none()
+    }
+
+    /// Collect the spans of `span_trace` with the rules applied, along with
+    /// the number of spans left out because of the limit
+    pub(crate) fn collect(&self, span_trace: &tracing_error::SpanTrace) -> (Vec<SpanFrame>, usize) {
+        let mut spans = SpanFrame::collect(span_trace);
+
+        let hidden = match self.limit {
+            Some(limit) if spans.len() > limit => {
+                let hidden = spans.len() - limit;
+                spans.truncate(limit);
+                hidden
+            }
+            _ => 0,
+        };
+
+        if !self.rules.is_empty() {
+            for span in &mut spans {
+                span.fields = self.filter_fields(&span.fields, &span.field_names);
+            }
+        }
+
+        (spans, hidden)
+    }
+
+    fn filter_fields(&self, fields: &str, names: &[&str]) -> String {
+        let mut out = String::new();
+
+        for (key, value) in split_fields(fields, names) {
+            // The `message` field is recorded without its name.
+            let name = key.unwrap_or("message");
+            let action = self
+                .rules
+                .iter()
+                .find(|(pattern, _)| glob_matches(pattern, name))
+                .map(|(_, action)| *action);
+
+            let value = match action {
+                Some(SpanFieldAction::Drop) => continue,
+                Some(SpanFieldAction::Redact) => "[redacted]".to_string(),
+                Some(SpanFieldAction::Truncate(max)) => truncate_value(value, max),
+                _ => value.to_string(),
+            };
+
+            if !out.is_empty() {
+                out.push(' ');
+            }
+
+            if let Some(key) = key {
+                out.push_str(key);
+                out.push('=');
+            }
+
+            out.push_str(&value);
+        }
+
+        out
+    }
+}
+
+/// Split formatted span fields, e.g. `path="a b" len=3`, into names and values
+///
+/// Only the `names` the span was declared with start a new field, so values
+/// like `query=a=b c=d` recorded with `%` stay in one piece.
+#[cfg(feature = "capture-spantrace")]
+fn split_fields<'a>(fields: &'a str, names: &[&str]) -> Vec<(Option<&'a str>, &'a str)> {
+    let mut out = Vec::new();
+    let mut rest = fields.trim();
+
+    while !rest.is_empty() {
+        let key = field_name(rest, names);
+        let start = key.map_or(0, |key| key.len() + 1);
+        let end = field_value_end(rest, start, names);
+        out.push((key, &rest[start..end]));
+        rest = rest[end..].trim_start();
+    }
+
+    out
+}
+
+/// The name of the field at the start of `s`, if any
+#[cfg(feature = "capture-spantrace")]
+fn field_name<'a>(s: &'a str, names: &[&str]) -> Option<&'a str> {
+    names.iter().find_map(|name| {
+        s.strip_prefix(name)
+            .filter(|rest| rest.starts_with('='))
+            .map(|_| &s[..name.len()])
+    })
+}
+
+/// The end of the value starting at `start`, i.e. the first space outside of
+/// quotes and brackets that is followed by another field
+#[cfg(feature = "capture-spantrace")]
+fn field_value_end(s: &str, start: usize, names: &[&str]) -> usize {
+    let mut quoted = false;
+    let mut escaped = false;
+    let mut depth = 0usize;
+
+    for (i, c) in s[start..].char_indices() {
+        let i = start + i;
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            _ if quoted => (),
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            ' ' if depth == 0 && field_name(&s[i + 1..], names).is_some() => return i,
+            _ => (),
+        }
+    }
+
+    s.len()
+}
+
+/// Shorten `value` to `max` characters, keeping the quotes of a string value
+#[cfg(feature = "capture-spantrace")]
+fn truncate_value(value: &str, max: usize) -> String {
+    let inner = value.strip_prefix('"').and_then(|v| v.strip_suffix('"'));
+
+    match inner {
+        Some(inner) if inner.chars().count() > max => {
+            let mut inner: String = inner.chars().take(max).collect();
+
+            // Don't leave the closing quote escaped.
+            let backslashes = inner.chars().rev().take_while(|c| *c == '\\').count();
+            if backslashes % 2 == 1 {
+                inner.pop();
+            }
+
+            format!("\"{}…\"", inner)
+        }
+        None if value.chars().count() > max => {
+            let mut value: String = value.chars().take(max).collect();
+            value.push('…');
+            value
+        }
+        _ => value.to_string(),
+    }
+}
+
+/// Match `name` against a pattern where `*` matches any number of characters
+#[cfg(feature = "capture-spantrace")]
+fn glob_matches(pattern: &str, name: &str) -> bool {
+    let mut parts: Vec<_> = pattern.split('*').collect();
+    let last = parts.pop().unwrap_or("");
+
+    let mut rest = match parts.first() {
+        Some(first) => match name.strip_prefix(first) {
+            Some(rest) => rest,
+            None => return false,
+        },
+        None => return name == last,
+    };
+
+    for part in &parts[1..] {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+
+    rest.ends_with(last)
+}
+
+/// The SPANTRACE section of a report, printed like `color_spantrace` does but
+/// with the span filter rules applied
+#[cfg(feature = "capture-spantrace")]
+pub(crate) struct SpanTraceSection<'a>(
+    pub(crate) &'a tracing_error::SpanTrace,
+    pub(crate) &'a SpanFilter,
+    pub(crate) Theme,
+);
+
+#[cfg(feature = "capture-spantrace")]
+impl fmt::Display for SpanTraceSection<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self(span_trace, filter, theme) = self;
+
+        if filter.is_empty() {
+            return write!(f, "{}", color_spantrace::colorize(span_trace));
+        }
+
+        writeln!(f, "{:━^80}\n", " SPANTRACE ")?;
+        write!(f, "{}", FilteredSpanTrace(span_trace, filter, *theme))
+    }
+}
+
+/// The spans of a `SpanTrace` with the span filter rules applied
+#[cfg(feature = "capture-spantrace")]
+pub(crate) struct FilteredSpanTrace<'a>(
+    pub(crate) &'a tracing_error::SpanTrace,
+    pub(crate) &'a SpanFilter,
+    pub(crate) Theme,
+);
+
+#[cfg(feature = "capture-spantrace")]
+impl fmt::Display for FilteredSpanTrace<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self(span_trace, filter, theme) = self;
+        let (spans, hidden) = filter.collect(span_trace);
+
+        let v = if std::thread::panicking() {
+            panic_verbosity()
+        } else {
+            lib_verbosity()
+        };
+
+        for (n, span) in spans.iter().enumerate() {
+            if n > 0 {
+                writeln!(f)?;
+            }
+
+            write!(f, "{:>2}: {}", n, StyledSpan(span, *theme))?;
+
+            if v >= Verbosity::Full {
+                write!(
+                    (&mut *f).header("\n").ready(),
+                    "{}",
+                    SourceSection(span.file.map(Path::new), span.line, *theme)
+                )?;
+            }
+        }
+
+        if hidden > 0 {
+            let hidden = format!(
+                "⋮ {} span{} hidden ⋮",
+                hidden,
+                if hidden == 1 { "" } else { "s" }
+            );
+            if !spans.is_empty() {
+                writeln!(f)?;
+            }
+
+            write!(f, "{:^80}", hidden.style(theme.hidden_frames))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "issue-url")]
+const DEFAULT_ISSUE_URL_MAX_LENGTH: usize = 8000;
+
 /// Builder for customizing the behavior of the global panic and error report hooks
 pub struct HookBuilder {
     filters: Vec<Box<FilterCallback>>,
     capture_span_trace_by_default: bool,
     display_env_section: bool,
+    display_fingerprint: bool,
+    environment: Option<crate::environment::Environment>,
+    trace_layout: TraceLayout,
+    report_layout: ReportLayout,
+    docs_url: Option<String>,
+    redactor: Redactor,
+    #[cfg(feature = "capture-spantrace")]
+    span_filter: SpanFilter,
     panic_section: Option<Box<dyn Display + Send + Sync + 'static>>,
     panic_message: Option<Box<dyn PanicMessage>>,
     theme: Theme,
     #[cfg(feature = "issue-url")]
     issue_url: Option<String>,
     #[cfg(feature = "issue-url")]
+    issue_tracker: Arc<dyn crate::issue::IssueTracker>,
+    #[cfg(feature = "issue-url")]
+    issue_url_max_length: usize,
+    #[cfg(feature = "issue-url")]
+    issue_template: Arc<crate::issue::IssueTemplate>,
+    #[cfg(feature = "issue-url")]
+    issue_bundle: Option<Arc<crate::issue::IssueBundle>>,
+    #[cfg(feature = "issue-url")]
+    issue_prompt: Option<Arc<crate::issue::IssuePrompt>>,
+    #[cfg(feature = "issue-url")]
     issue_metadata: Vec<(String, Box<dyn Display + Send + Sync + 'static>)>,
     #[cfg(feature = "issue-url")]
     issue_filter: Arc<IssueFilterCallback>,
+    #[cfg(feature = "crash-report")]
+    crash_reporter: Option<crate::crash_report::CrashReporter>,
 }
 
 impl HookBuilder {
@@ -459,15 +1213,35 @@ impl HookBuilder {
             filters: vec![],
             capture_span_trace_by_default: false,
             display_env_section: true,
+            display_fingerprint: false,
+            environment: None,
+            trace_layout: TraceLayout::Separate,
+            report_layout: ReportLayout::new(),
+            docs_url: None,
+            redactor: Redactor::default(),
+            #[cfg(feature = "capture-spantrace")]
+            span_filter: SpanFilter::default(),
             panic_section: None,
             panic_message: None,
             theme: Theme::dark(),
             #[cfg(feature = "issue-url")]
             issue_url: None,
             #[cfg(feature = "issue-url")]
+            issue_tracker: Arc::new(crate::issue::GitHub),
+            #[cfg(feature = "issue-url")]
+            issue_url_max_length: DEFAULT_ISSUE_URL_MAX_LENGTH,
+            #[cfg(feature = "issue-url")]
+            issue_template: Arc::new(crate::issue::IssueTemplate::new()),
+            #[cfg(feature = "issue-url")]
+            issue_bundle: None,
+            #[cfg(feature = "issue-url")]
+            issue_prompt: None,
+            #[cfg(feature = "issue-url")]
             issue_metadata: vec![],
             #[cfg(feature = "issue-url")]
-            issue_filter: Arc::new(|_| true),
+            issue_filter: Arc::new(|_| crate::issue::IssueAction::Create.into()),
+            #[cfg(feature = "crash-report")]
+            crash_reporter: None,
         }
     }
 
@@ -552,7 +1326,8 @@ impl HookBuilder {
         self
     }
 
-    /// Set an upstream github repo and enable issue reporting url generation
+    /// Set the new issue url of an upstream repo and enable issue reporting url
+    /// generation
     ///
     /// # Details
     ///
@@ -563,6 +1338,9 @@ impl HookBuilder {
     /// generated urls by calling `add_issue_metadata` when configuring the
     /// HookBuilder.
     ///
+    /// The urls are generated for GitHub unless another tracker is configured
+    /// with `issue_tracker`.
+    ///
     /// # Examples
     ///
     /// ```rust
@@ -578,9 +1356,132 @@ impl HookBuilder {
         self
     }
 
-    /// Add a new entry to the metadata table in generated github issue urls
+    /// Configures the issue tracker that issue urls are generated for
     ///
-    /// **Note**: this metadata will be ignored if no `issue_url` is set.
+    /// # Details
+    ///
+    /// Defaults to `GitHub`. The tracker determines the query parameters of the
+    /// generated url and the markup of the issue body.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use color_eyre::issue::GitLab;
+    ///
+    /// color_eyre::config::HookBuilder::default()
+    ///     .issue_url("https://gitlab.example.com/tools/cli/-/issues/new")
+    ///     .issue_tracker(GitLab)
+    ///     .install()
+    ///     .unwrap();
+    /// ```
+    #[cfg(feature = "issue-url")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "issue-url")))]
+    pub fn issue_tracker<T>(mut self, tracker: T) -> Self
+    where
+        T: crate::issue::IssueTracker,
+    {
+        self.issue_tracker = Arc::new(tracker);
+        self
+    }
+
+    /// Configures the maximum length of generated issue urls
+    ///
+    /// # Details
+    ///
+    /// Browsers and issue trackers reject urls longer than about 8KB, which
+    /// is the default. When the url of an issue would be longer, the
+    /// backtrace is shortened to fewer and fewer frames, then the span trace
+    /// is collapsed to span names, and finally the rest of the report is
+    /// truncated. The error message and metadata are always kept, and a note
+    /// in the issue body explains what was left out.
+    #[cfg(feature = "issue-url")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "issue-url")))]
+    pub fn issue_url_max_length(mut self, max_length: usize) -> Self {
+        self.issue_url_max_length = max_length;
+        self
+    }
+
+    /// Configures the title, body, labels and assignees of generated issues
+    ///
+    /// See `IssueTemplate` for the placeholders that can be used in the
+    /// title and body.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use color_eyre::issue::IssueTemplate;
+    ///
+    /// color_eyre::config::HookBuilder::default()
+    ///     .issue_url(concat!(env!("CARGO_PKG_REPOSITORY"), "/issues/new"))
+    ///     .issue_template(IssueTemplate::new().title("crash: {message}").label("bug"))
+    ///     .install()
+    ///     .unwrap();
+    /// ```
+    #[cfg(feature = "issue-url")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "issue-url")))]
+    pub fn issue_template(mut self, template: crate::issue::IssueTemplate) -> Self {
+        self.issue_template = Arc::new(template);
+        self
+    }
+
+    /// Configures saving offline bug report bundles
+    ///
+    /// The bundles contain the same information as generated issues, for
+    /// users who can't open the issue url. Can be used with or without an
+    /// `issue_url`. See `IssueBundle` for details.
+    #[cfg(feature = "issue-url")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "issue-url")))]
+    pub fn issue_bundle(mut self, bundle: crate::issue::IssueBundle) -> Self {
+        self.issue_bundle = Some(Arc::new(bundle));
+        self
+    }
+
+    /// Configures asking whether to open the issue after a panic
+    ///
+    /// When running in a terminal, the panic hook shows a preview of the
+    /// issue, with redactions applied, and asks whether to open it in the
+    /// browser or save its url to a file. Otherwise only the url is printed,
+    /// as without a prompt. Use `color_eyre::issue::prompt` to do the same
+    /// for `Report`s. See `IssuePrompt` for details.
+    #[cfg(feature = "issue-url")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "issue-url")))]
+    pub fn issue_prompt(mut self, prompt: crate::issue::IssuePrompt) -> Self {
+        self.issue_prompt = Some(Arc::new(prompt));
+        self
+    }
+
+    /// Configures sending crash reports of panics to an endpoint
+    ///
+    /// Reports are sent after the panic report is printed, with a single
+    /// attempt limited to the reporter's `panic_timeout`, and can be sent
+    /// for `Report`s with `color_eyre::crash_report::send`. If the crash
+    /// reporter has a spool directory, `install` sends the reports spooled
+    /// there in a background thread. See `CrashReporter` for details.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use color_eyre::crash_report::CrashReporter;
+    ///
+    /// color_eyre::config::HookBuilder::default()
+    ///     .crash_reporter(
+    ///         CrashReporter::new("http://crashes.internal:8080/report")
+    ///             .unwrap()
+    ///             .spool_dir(std::env::temp_dir().join("my-app-crashes")),
+    ///     )
+    ///     .install()
+    ///     .unwrap();
+    /// ```
+    #[cfg(feature = "crash-report")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "crash-report")))]
+    pub fn crash_reporter(mut self, reporter: crate::crash_report::CrashReporter) -> Self {
+        self.crash_reporter = Some(reporter);
+        self
+    }
+
+    /// Add a new entry to the metadata table in generated issue urls
+    ///
+    /// **Note**: this metadata will be ignored if no `issue_url` is set.
     ///
     /// # Examples
     ///
@@ -605,14 +1506,20 @@ impl HookBuilder {
 
     /// Configures a filter for disabling issue url generation for certain kinds of errors
     ///
-    /// If the closure returns `true`, then the issue url will be generated.
+    /// The closure receives an `IssueContext` with the kind of error, its
+    /// `Fingerprint`, location, backtrace frames and attached sections, and
+    /// returns an `IssueDecision`, an `IssueAction` or a `bool`. If it returns
+    /// `true`, then the issue url will be generated. If it returns
+    /// `IssueAction::Search`, then a url that searches for existing issues
+    /// with the same fingerprint is generated instead. An `IssueDecision` can
+    /// also add labels and metadata to the issue.
     ///
     /// # Examples
     ///
     /// ```rust
     /// color_eyre::config::HookBuilder::default()
     ///     .issue_url(concat!(env!("CARGO_PKG_REPOSITORY"), "/issues/new"))
-    ///     .issue_filter(|kind| match kind {
+    ///     .issue_filter(|context| match context.kind() {
     ///         color_eyre::ErrorKind::NonRecoverable(payload) => {
     ///             let payload = payload
     ///                 .downcast_ref::<String>()
@@ -626,14 +1533,35 @@ impl HookBuilder {
     ///     })
     ///     .install()
     ///     .unwrap();
+    /// ```
+    ///
+    /// Linking to existing issues for bugs that were already reported:
+    ///
+    /// ```rust
+    /// use color_eyre::issue::IssueAction;
+    ///
+    /// let known = ["f4c2f8bd6ad0be1b", "08d35b4d6e5d4c1a"];
     ///
+    /// color_eyre::config::HookBuilder::default()
+    ///     .issue_url(concat!(env!("CARGO_PKG_REPOSITORY"), "/issues/new"))
+    ///     .issue_filter(move |context| {
+    ///         if known.contains(&context.fingerprint().to_string().as_str()) {
+    ///             IssueAction::Search
+    ///         } else {
+    ///             IssueAction::Create
+    ///         }
+    ///     })
+    ///     .install()
+    ///     .unwrap();
+    /// ```
     #[cfg(feature = "issue-url")]
     #[cfg_attr(docsrs, doc(cfg(feature = "issue-url")))]
-    pub fn issue_filter<F>(mut self, predicate: F) -> Self
+    pub fn issue_filter<F, R>(mut self, predicate: F) -> Self
     where
-        F: Fn(crate::ErrorKind<'_>) -> bool + Send + Sync + 'static,
+        F: Fn(&crate::issue::IssueContext<'_>) -> R + Send + Sync + 'static,
+        R: Into<crate::issue::IssueDecision>,
     {
-        self.issue_filter = Arc::new(predicate);
+        self.issue_filter = Arc::new(move |context| predicate(context).into());
         self
     }
 
@@ -649,6 +1577,168 @@ impl HookBuilder {
         self
     }
 
+    /// Configures whether the `Fingerprint` of the report is displayed
+    ///
+    /// The fingerprint identifies the bug behind a report across occurrences,
+    /// see `Handler::fingerprint` and `PanicReport::fingerprint`.
+    pub fn display_fingerprint(mut self, cond: bool) -> Self {
+        self.display_fingerprint = cond;
+        self
+    }
+
+    /// Configures collecting information about the environment for panic
+    /// reports and generated issues
+    ///
+    /// The collected entries are printed in panic reports and added to the
+    /// start of the issue metadata. See `Environment` for details.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// color_eyre::config::HookBuilder::default()
+    ///     .environment_metadata(color_eyre::environment!())
+    ///     .install()
+    ///     .unwrap();
+    /// ```
+    pub fn environment_metadata(mut self, environment: crate::environment::Environment) -> Self {
+        self.environment = Some(environment);
+        self
+    }
+
+    /// Configures how the `SpanTrace` and `Backtrace` are laid out in error
+    /// reports and panics
+    ///
+    /// Only has an effect when a `SpanTrace` is captured.
+    pub fn trace_layout(mut self, layout: TraceLayout) -> Self {
+        self.trace_layout = layout;
+        self
+    }
+
+    /// Set the template of the documentation links of error codes, with
+    /// `{code}` replaced by the code set with `Section::code`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// color_eyre::config::HookBuilder::default()
+    ///     .error_docs_url("https://docs.example.com/errors/{code}")
+    ///     .install()
+    ///     .unwrap();
+    /// ```
+    pub fn error_docs_url<S: ToString>(mut self, template: S) -> Self {
+        self.docs_url = Some(template.to_string());
+        self
+    }
+
+    /// Configures the order of the parts of error reports, how their sections
+    /// are grouped and which parts are hidden
+    ///
+    /// See `ReportLayout` for the default order.
+    pub fn report_layout(mut self, layout: ReportLayout) -> Self {
+        self.report_layout = layout;
+        self
+    }
+
+    /// Add a rule for printing the span fields with a matching name
+    ///
+    /// # Details
+    ///
+    /// The pattern is matched against the name of each field of every span in
+    /// the `SpanTrace`, where `*` matches any number of characters. The first
+    /// matching rule is applied. The rules apply to the printed report, to
+    /// the `SpanTrace` included in issue urls and to the spans of crash
+    /// reports.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use color_eyre::config::{HookBuilder, SpanFieldAction};
+    ///
+    /// HookBuilder::default()
+    ///     .span_field_rule("password", SpanFieldAction::Drop)
+    ///     .span_field_rule("*token*", SpanFieldAction::Redact)
+    ///     .span_field_rule("body", SpanFieldAction::Truncate(32))
+    ///     .install()
+    ///     .unwrap();
+    /// ```
+    #[cfg(feature = "capture-spantrace")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "capture-spantrace")))]
+    pub fn span_field_rule(mut self, pattern: impl Into<String>, action: SpanFieldAction) -> Self {
+        self.span_filter.rules.push((pattern.into(), action));
+        self
+    }
+
+    /// Limit the number of spans printed from a `SpanTrace`, starting with
+    /// the innermost span
+    #[cfg(feature = "capture-spantrace")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "capture-spantrace")))]
+    pub fn span_limit(mut self, limit: usize) -> Self {
+        self.span_filter.limit = Some(limit);
+        self
+    }
+
+    /// Redact sensitive text of the given kind from error reports, panic
+    /// reports and issue urls
+    ///
+    /// # Details
+    ///
+    /// Redactions are applied to each line of the rendered report, including
+    /// error messages, sections, issue metadata and backtraces, and to the
+    /// body of the issue url before it is encoded. Detected text is replaced
+    /// with `[redacted]`, keeping any styles around it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use color_eyre::config::{HookBuilder, Redaction};
+    ///
+    /// HookBuilder::default()
+    ///     .add_redaction(Redaction::BearerTokens)
+    ///     .add_redaction(Redaction::Emails)
+    ///     .install()
+    ///     .unwrap();
+    /// ```
+    pub fn add_redaction(mut self, redaction: Redaction) -> Self {
+        self.redactor.push_redaction(redaction);
+        self
+    }
+
+    /// Add a custom rule for redacting sensitive text from error reports,
+    /// panic reports and issue urls
+    ///
+    /// # Details
+    ///
+    /// The rule is called with each line of the rendered report, which may
+    /// contain ANSI escape sequences, and replaces any sensitive text in
+    /// place. Rules are applied in the order they were added, so matching
+    /// with a regular expression works just as well as the plain search
+    /// below.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// color_eyre::config::HookBuilder::default()
+    ///     .add_redaction_rule(Box::new(|report| {
+    ///         if let Ok(password) = std::env::var("DB_PASSWORD") {
+    ///             *report = report.replace(&password, "[redacted]");
+    ///         }
+    ///     }))
+    ///     .install()
+    ///     .unwrap();
+    /// ```
+    pub fn add_redaction_rule(mut self, rule: Box<RedactCallback>) -> Self {
+        self.redactor.push(rule);
+        self
+    }
+
+    /// Redact all kinds of sensitive text detected by the built-in
+    /// `Redaction`s
+    pub fn add_default_redactions(self) -> Self {
+        Redaction::ALL
+            .iter()
+            .fold(self, |builder, redaction| builder.add_redaction(*redaction))
+    }
+
     /// Add a custom filter to the set of frame filters
     ///
     /// # Examples
@@ -683,8 +1773,18 @@ impl HookBuilder {
     /// Install the given Hook as the global error report hook
     pub fn install(self) -> Result<(), crate::eyre::Report> {
         let (panic_hook, eyre_hook) = self.into_hooks();
+
+        #[cfg(feature = "crash-report")]
+        let crash_reporter = panic_hook.crash_reporter.clone();
+
         eyre_hook.install()?;
         panic_hook.install();
+
+        #[cfg(feature = "crash-report")]
+        if let Some(crash_reporter) = crash_reporter {
+            std::thread::spawn(move || crash_reporter.send_spooled());
+        }
+
         Ok(())
     }
 
@@ -699,13 +1799,38 @@ impl HookBuilder {
     pub fn into_hooks(self) -> (PanicHook, EyreHook) {
         let theme = self.theme;
         #[cfg(feature = "issue-url")]
-        let metadata = Arc::new(self.issue_metadata);
+        let metadata = {
+            let mut metadata = self
+                .environment
+                .as_ref()
+                .map(crate::environment::Environment::metadata)
+                .unwrap_or_default();
+            metadata.extend(self.issue_metadata);
+            Arc::new(metadata)
+        };
+        #[cfg(feature = "crash-report")]
+        let crash_reporter = {
+            let environment = &self.environment;
+            self.crash_reporter.map(|mut reporter| {
+                reporter.environment = environment.clone();
+                Arc::new(reporter)
+            })
+        };
+        let redactor = Arc::new(self.redactor);
+        #[cfg(feature = "capture-spantrace")]
+        let span_filter = Arc::new(self.span_filter);
         let panic_hook = PanicHook {
             filters: self.filters.into(),
             section: self.panic_section,
             #[cfg(feature = "capture-spantrace")]
             capture_span_trace_by_default: self.capture_span_trace_by_default,
+            #[cfg(feature = "capture-spantrace")]
+            span_filter: span_filter.clone(),
             display_env_section: self.display_env_section,
+            display_fingerprint: self.display_fingerprint,
+            environment: self.environment,
+            trace_layout: self.trace_layout,
+            redactor: redactor.clone(),
             panic_message: self
                 .panic_message
                 .unwrap_or_else(|| Box::new(DefaultPanicMessage(theme))),
@@ -713,23 +1838,54 @@ impl HookBuilder {
             #[cfg(feature = "issue-url")]
             issue_url: self.issue_url.clone(),
             #[cfg(feature = "issue-url")]
+            issue_tracker: self.issue_tracker.clone(),
+            #[cfg(feature = "issue-url")]
+            issue_url_max_length: self.issue_url_max_length,
+            #[cfg(feature = "issue-url")]
+            issue_template: self.issue_template.clone(),
+            #[cfg(feature = "issue-url")]
+            issue_bundle: self.issue_bundle.clone(),
+            #[cfg(feature = "issue-url")]
+            issue_prompt: self.issue_prompt.clone(),
+            #[cfg(feature = "issue-url")]
             issue_metadata: metadata.clone(),
             #[cfg(feature = "issue-url")]
             issue_filter: self.issue_filter.clone(),
+            #[cfg(feature = "crash-report")]
+            crash_reporter: crash_reporter.clone(),
         };
 
         let eyre_hook = EyreHook {
             filters: panic_hook.filters.clone(),
             #[cfg(feature = "capture-spantrace")]
             capture_span_trace_by_default: self.capture_span_trace_by_default,
+            #[cfg(feature = "capture-spantrace")]
+            span_filter,
             display_env_section: self.display_env_section,
+            display_fingerprint: self.display_fingerprint,
+            trace_layout: self.trace_layout,
+            report_layout: Arc::new(self.report_layout),
+            docs_url: self.docs_url,
+            redactor,
             theme,
             #[cfg(feature = "issue-url")]
             issue_url: self.issue_url,
             #[cfg(feature = "issue-url")]
+            issue_tracker: self.issue_tracker,
+            #[cfg(feature = "issue-url")]
+            issue_url_max_length: self.issue_url_max_length,
+            #[cfg(feature = "issue-url")]
+            issue_template: self.issue_template,
+            #[cfg(feature = "issue-url")]
+            issue_bundle: self.issue_bundle,
+            #[cfg(feature = "issue-url")]
+            issue_prompt: self.issue_prompt,
+            #[cfg(feature = "issue-url")]
             issue_metadata: metadata,
             #[cfg(feature = "issue-url")]
             issue_filter: self.issue_filter,
+            #[cfg(feature = "crash-report")]
+            crash_reporter,
         };
 
         #[cfg(feature = "capture-spantrace")]
@@ -758,23 +1914,37 @@ impl Default for HookBuilder {
     }
 }
 
-fn default_frame_filter(frames: &mut Vec<&Frame>) {
+pub(crate) fn default_frame_filter(frames: &mut Vec<&Frame>) {
     let top_cutoff = frames
         .iter()
-        .rposition(|x| x.is_post_panic_code())
-        .map(|x| x + 2) // indices are 1 based
-        .unwrap_or(0);
+        .rfind(|x| x.is_post_panic_code())
+        .map_or(0, |x| x.n + 1);
 
     let bottom_cutoff = frames
         .iter()
-        .position(|x| x.is_runtime_init_code())
-        .unwrap_or_else(|| frames.len());
+        .find(|x| x.is_runtime_init_code())
+        .map_or(usize::MAX, |x| x.n - 1);
 
     let rng = top_cutoff..=bottom_cutoff;
     frames.retain(|x| rng.contains(&x.n))
 }
 
-fn eyre_frame_filters(frames: &mut Vec<&Frame>) {
+/// Apply the given filters and color-eyre's own filters to `frames`, keeping
+/// them in order
+pub(crate) fn filter_frames<'a>(
+    frames: &'a [Frame],
+    filters: &[Box<FilterCallback>],
+) -> Vec<&'a Frame> {
+    let mut filtered: Vec<_> = frames.iter().collect();
+    for filter in filters {
+        filter(&mut filtered);
+    }
+    eyre_frame_filters(&mut filtered);
+    filtered.sort_by_key(|frame| frame.n);
+    filtered
+}
+
+pub(crate) fn eyre_frame_filters(frames: &mut Vec<&Frame>) {
     let filters = &[
         "<color_eyre::Handler as eyre::EyreHandler>::default",
         "eyre::",
@@ -833,9 +2003,170 @@ pub struct PanicReport<'a> {
     backtrace: Option<backtrace::Backtrace>,
     #[cfg(feature = "capture-spantrace")]
     span_trace: Option<tracing_error::SpanTrace>,
+    sections: Vec<crate::section::help::HelpInfo>,
+}
+
+impl PanicReport<'_> {
+    /// The fingerprint of the bug behind this panic
+    ///
+    /// Computed from the panic payload, the file of the panic location and
+    /// the top frames of the backtrace. See `Fingerprint` for details.
+    pub fn fingerprint(&self) -> crate::Fingerprint {
+        let payload = self.panic_info.payload();
+        let payload = payload
+            .downcast_ref::<String>()
+            .map(String::as_str)
+            .or_else(|| payload.downcast_ref::<&str>().cloned())
+            .unwrap_or("<non string panic payload>");
+
+        crate::fingerprint::FingerprintBuilder::new()
+            .message(payload)
+            .location(self.panic_info.location())
+            .backtrace(self.backtrace.as_ref(), &self.hook.filters)
+            .finish()
+    }
+}
+
+#[cfg(feature = "crash-report")]
+impl PanicReport<'_> {
+    fn crash_report(&self) -> crate::crash_report::CrashReport<'_> {
+        let payload = self.panic_info.payload();
+        let payload = payload
+            .downcast_ref::<String>()
+            .map(String::as_str)
+            .or_else(|| payload.downcast_ref::<&str>().cloned())
+            .unwrap_or("<non string panic payload>");
+
+        let mut report = crate::crash_report::CrashReport::new(
+            "panic",
+            payload.to_string(),
+            self.fingerprint(),
+            &self.hook.filters,
+            &self.hook.redactor,
+        );
+        report.fields = self
+            .sections
+            .iter()
+            .filter_map(|section| crate::section::SectionRef(section).field())
+            .collect();
+        report.location = self.panic_info.location();
+        report.backtrace = self.backtrace.as_ref();
+
+        #[cfg(feature = "capture-spantrace")]
+        report.set_span_trace(self.span_trace.as_ref(), &self.hook.span_filter);
+
+        report
+    }
+}
+
+#[cfg(feature = "issue-url")]
+impl PanicReport<'_> {
+    /// The issue section for this panic, or `None` if no issue is linked or
+    /// the issue filter skips it
+    fn issue_section(&self) -> Option<crate::section::issue::IssueSection<'_>> {
+        let hook = self.hook;
+
+        if hook.issue_url.is_none() && hook.issue_bundle.is_none() {
+            return None;
+        }
+
+        let payload = self.panic_info.payload();
+        let fingerprint = self.fingerprint();
+        let frames = self
+            .backtrace
+            .as_ref()
+            .map(Frame::collect)
+            .unwrap_or_default();
+        let decision = (*hook.issue_filter)(&crate::issue::IssueContext {
+            kind: crate::ErrorKind::NonRecoverable(payload),
+            fingerprint,
+            location: self.panic_info.location(),
+            frames: filter_frames(&frames, &hook.filters),
+            sections: &self.sections,
+        });
+
+        if decision.action == crate::issue::IssueAction::Skip {
+            return None;
+        }
+
+        let url = hook.issue_url.as_deref().unwrap_or_default();
+        let payload = payload
+            .downcast_ref::<String>()
+            .map(String::as_str)
+            .or_else(|| payload.downcast_ref::<&str>().cloned())
+            .unwrap_or("<non string panic payload>");
+
+        let issue_section = crate::section::issue::IssueSection::new(url, payload)
+            .with_fingerprint(fingerprint)
+            .with_decision(decision)
+            .with_tracker(&*hook.issue_tracker)
+            .with_max_len(hook.issue_url_max_length)
+            .with_template(&hook.issue_template)
+            .with_backtrace(self.backtrace.as_ref())
+            .with_location(self.panic_info.location())
+            .with_metadata(&hook.issue_metadata)
+            .with_sections(&self.sections)
+            .with_redactor(&hook.redactor);
+
+        #[cfg(feature = "capture-spantrace")]
+        let issue_section = issue_section
+            .with_span_trace(self.span_trace.as_ref())
+            .with_span_filter(&hook.span_filter);
+
+        Some(issue_section)
+    }
+
+    /// The issue for this panic, saving a bug report bundle if
+    /// `save_bundle` is set and one is configured with
+    /// `HookBuilder::issue_bundle`
+    fn issue(&self, save_bundle: bool) -> PanicIssue<'_> {
+        let section = self.issue_section();
+        let bundle = match (&self.hook.issue_bundle, &section) {
+            (Some(bundle), Some(section)) if save_bundle => {
+                Some(crate::section::issue::save_bundle(section.clone(), bundle))
+            }
+            _ => None,
+        };
+
+        PanicIssue { section, bundle }
+    }
+
+    /// Ask whether to open the issue for this panic, if configured with
+    /// `HookBuilder::issue_prompt`
+    fn prompt_issue(&self, issue: &PanicIssue<'_>) -> std::io::Result<()> {
+        match (
+            &self.hook.issue_prompt,
+            &self.hook.issue_url,
+            &issue.section,
+        ) {
+            (Some(prompt), Some(_), Some(issue_section)) => issue_section.prompt(prompt),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The issue for a panic, built once for both its report and the prompt
+#[cfg(feature = "issue-url")]
+struct PanicIssue<'a> {
+    section: Option<crate::section::issue::IssueSection<'a>>,
+    /// Where the bug report bundle was saved, if it was
+    bundle: Option<std::io::Result<std::path::PathBuf>>,
+}
+
+struct FormattedPanicInfo<'a, 'b> {
+    report: &'a PanicReport<'b>,
+    #[cfg(feature = "issue-url")]
+    issue: &'a PanicIssue<'a>,
+}
+
+impl fmt::Display for FormattedPanicInfo<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        print_panic_info(self, f)
+    }
 }
 
-fn print_panic_info(report: &PanicReport<'_>, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+fn print_panic_info(info: &FormattedPanicInfo<'_, '_>, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let report = info.report;
     report.hook.panic_message.display(report.panic_info, f)?;
 
     let v = panic_verbosity();
@@ -847,19 +2178,52 @@ fn print_panic_info(report: &PanicReport<'_>, f: &mut fmt::Formatter<'_>) -> fmt
         write!(&mut separated.ready(), "{}", section)?;
     }
 
+    let fields: Vec<_> = report
+        .sections
+        .iter()
+        .filter_map(|section| crate::section::SectionRef(section).field())
+        .collect();
+
+    if !fields.is_empty() {
+        write!(
+            &mut separated.ready(),
+            "{}",
+            crate::SectionExt::header(
+                crate::section::help::FieldsSection(fields).to_string(),
+                "Context:"
+            )
+        )?;
+    }
+
+    let fmted_bt = report.backtrace.as_ref().map(|bt| {
+        let fmted_bt = report.hook.format_backtrace(bt);
+
+        #[cfg(feature = "capture-spantrace")]
+        let fmted_bt = fmted_bt.with_span_trace(report.span_trace.as_ref());
+
+        fmted_bt
+    });
+
     #[cfg(feature = "capture-spantrace")]
     {
-        if let Some(span_trace) = report.span_trace.as_ref() {
+        if let Some(span_trace) = report
+            .span_trace
+            .as_ref()
+            .filter(|_| !fmted_bt.iter().any(|bt| bt.is_merged()))
+        {
             write!(
                 &mut separated.ready(),
                 "{}",
-                crate::writers::FormattedSpanTrace(span_trace)
+                crate::writers::FormattedSpanTrace(
+                    span_trace,
+                    &report.hook.span_filter,
+                    report.hook.theme
+                )
             )?;
         }
     }
 
-    if let Some(bt) = report.backtrace.as_ref() {
-        let fmted_bt = report.hook.format_backtrace(&bt);
+    if let Some(fmted_bt) = fmted_bt {
         write!(
             indented(&mut separated.ready()).with_format(Format::Uniform { indentation: "  " }),
             "{}",
@@ -867,6 +2231,22 @@ fn print_panic_info(report: &PanicReport<'_>, f: &mut fmt::Formatter<'_>) -> fmt
         )?;
     }
 
+    if let Some(environment) = &report.hook.environment {
+        write!(
+            &mut separated.ready(),
+            "{}",
+            crate::SectionExt::header(environment.clone(), "Environment:")
+        )?;
+    }
+
+    if report.hook.display_fingerprint {
+        write!(
+            &mut separated.ready(),
+            "Fingerprint: {}",
+            report.fingerprint()
+        )?;
+    }
+
     if report.hook.display_env_section {
         let env_section = EnvSection {
             bt_captured: &capture_bt,
@@ -878,37 +2258,46 @@ fn print_panic_info(report: &PanicReport<'_>, f: &mut fmt::Formatter<'_>) -> fmt
     }
 
     #[cfg(feature = "issue-url")]
-    {
-        let payload = report.panic_info.payload();
-
-        if report.hook.issue_url.is_some()
-            && (*report.hook.issue_filter)(crate::ErrorKind::NonRecoverable(payload))
-        {
-            let url = report.hook.issue_url.as_ref().unwrap();
-            let payload = payload
-                .downcast_ref::<String>()
-                .map(String::as_str)
-                .or_else(|| payload.downcast_ref::<&str>().cloned())
-                .unwrap_or("<non string panic payload>");
-
-            let issue_section = crate::section::github::IssueSection::new(url, payload)
-                .with_backtrace(report.backtrace.as_ref())
-                .with_location(report.panic_info.location())
-                .with_metadata(&**report.hook.issue_metadata);
-
-            #[cfg(feature = "capture-spantrace")]
-            let issue_section = issue_section.with_span_trace(report.span_trace.as_ref());
+    if let (Some(bundle), Some(saved)) = (&report.hook.issue_bundle, &info.issue.bundle) {
+        let bundle_section = crate::section::issue::BundleSection { saved, bundle };
+        write!(&mut separated.ready(), "{}", bundle_section)?;
+    }
 
-            write!(&mut separated.ready(), "{}", issue_section)?;
-        }
+    #[cfg(feature = "issue-url")]
+    if let (Some(_), Some(issue_section)) = (&report.hook.issue_url, &info.issue.section) {
+        write!(&mut separated.ready(), "{}", issue_section)?;
     }
 
     Ok(())
 }
 
+impl<'a> PanicReport<'a> {
+    /// The redacted report, printed with an already built issue
+    fn formatted<'b>(
+        &'b self,
+        #[cfg(feature = "issue-url")] issue: &'b PanicIssue<'b>,
+    ) -> impl fmt::Display + 'b {
+        Redacted(
+            &self.hook.redactor,
+            FormattedPanicInfo {
+                report: self,
+                #[cfg(feature = "issue-url")]
+                issue,
+            },
+        )
+    }
+}
+
 impl<'a, 'b> fmt::Display for PanicReport<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        print_panic_info(self, f)
+        #[cfg(feature = "issue-url")]
+        let issue = self.issue(false);
+
+        let formatted = self.formatted(
+            #[cfg(feature = "issue-url")]
+            &issue,
+        );
+        write!(f, "{}", formatted)
     }
 }
 
@@ -920,13 +2309,31 @@ pub struct PanicHook {
     theme: Theme,
     #[cfg(feature = "capture-spantrace")]
     capture_span_trace_by_default: bool,
+    #[cfg(feature = "capture-spantrace")]
+    span_filter: Arc<SpanFilter>,
     display_env_section: bool,
+    display_fingerprint: bool,
+    environment: Option<crate::environment::Environment>,
+    trace_layout: TraceLayout,
+    redactor: Arc<Redactor>,
     #[cfg(feature = "issue-url")]
     issue_url: Option<String>,
     #[cfg(feature = "issue-url")]
+    issue_tracker: Arc<dyn crate::issue::IssueTracker>,
+    #[cfg(feature = "issue-url")]
+    issue_url_max_length: usize,
+    #[cfg(feature = "issue-url")]
+    issue_template: Arc<crate::issue::IssueTemplate>,
+    #[cfg(feature = "issue-url")]
+    issue_bundle: Option<Arc<crate::issue::IssueBundle>>,
+    #[cfg(feature = "issue-url")]
+    issue_prompt: Option<Arc<crate::issue::IssuePrompt>>,
+    #[cfg(feature = "issue-url")]
     issue_metadata: Arc<Vec<(String, Box<dyn Display + Send + Sync + 'static>)>>,
     #[cfg(feature = "issue-url")]
     issue_filter: Arc<IssueFilterCallback>,
+    #[cfg(feature = "crash-report")]
+    crash_reporter: Option<Arc<crate::crash_report::CrashReporter>>,
 }
 
 impl PanicHook {
@@ -938,6 +2345,11 @@ impl PanicHook {
             filters: &self.filters,
             inner: trace,
             theme: self.theme,
+            #[cfg(feature = "capture-spantrace")]
+            span_trace: None,
+            #[cfg(feature = "capture-spantrace")]
+            span_filter: &self.span_filter,
+            layout: self.trace_layout,
         }
     }
 
@@ -958,7 +2370,29 @@ impl PanicHook {
         self,
     ) -> Box<dyn Fn(&std::panic::PanicInfo<'_>) + Send + Sync + 'static> {
         Box::new(move |panic_info| {
-            eprintln!("{}", self.panic_report(panic_info));
+            let report = self.panic_report(panic_info);
+
+            #[cfg(feature = "issue-url")]
+            let issue = report.issue(true);
+
+            eprintln!(
+                "{}",
+                report.formatted(
+                    #[cfg(feature = "issue-url")]
+                    &issue
+                )
+            );
+
+            #[cfg(feature = "crash-report")]
+            if let Some(crash_reporter) = &self.crash_reporter {
+                let delivery = crash_reporter.deliver_panic(&report.crash_report());
+                eprintln!("{}", crash_reporter.describe(&delivery));
+            }
+
+            #[cfg(feature = "issue-url")]
+            if let Err(error) = report.prompt_issue(&issue) {
+                eprintln!("Failed to open the issue: {}", error);
+            }
         })
     }
 
@@ -989,6 +2423,7 @@ impl PanicHook {
             #[cfg(feature = "capture-spantrace")]
             span_trace,
             backtrace,
+            sections: crate::scope::panic_sections(),
             hook: self,
         }
     }
@@ -999,14 +2434,33 @@ pub struct EyreHook {
     filters: Arc<[Box<FilterCallback>]>,
     #[cfg(feature = "capture-spantrace")]
     capture_span_trace_by_default: bool,
+    #[cfg(feature = "capture-spantrace")]
+    span_filter: Arc<SpanFilter>,
     display_env_section: bool,
+    display_fingerprint: bool,
+    trace_layout: TraceLayout,
+    report_layout: Arc<ReportLayout>,
+    docs_url: Option<String>,
+    redactor: Arc<Redactor>,
     theme: Theme,
     #[cfg(feature = "issue-url")]
     issue_url: Option<String>,
     #[cfg(feature = "issue-url")]
+    issue_tracker: Arc<dyn crate::issue::IssueTracker>,
+    #[cfg(feature = "issue-url")]
+    issue_url_max_length: usize,
+    #[cfg(feature = "issue-url")]
+    issue_template: Arc<crate::issue::IssueTemplate>,
+    #[cfg(feature = "issue-url")]
+    issue_bundle: Option<Arc<crate::issue::IssueBundle>>,
+    #[cfg(feature = "issue-url")]
+    issue_prompt: Option<Arc<crate::issue::IssuePrompt>>,
+    #[cfg(feature = "issue-url")]
     issue_metadata: Arc<Vec<(String, Box<dyn Display + Send + Sync + 'static>)>>,
     #[cfg(feature = "issue-url")]
     issue_filter: Arc<IssueFilterCallback>,
+    #[cfg(feature = "crash-report")]
+    crash_reporter: Option<Arc<crate::crash_report::CrashReporter>>,
 }
 
 impl EyreHook {
@@ -1032,14 +2486,35 @@ impl EyreHook {
             backtrace,
             #[cfg(feature = "capture-spantrace")]
             span_trace,
-            sections: Vec::new(),
+            sections: crate::scope::report_sections(),
+            error_limit: None,
+            code: None,
+            docs_url: self.docs_url.clone(),
             display_env_section: self.display_env_section,
+            display_fingerprint: self.display_fingerprint,
+            trace_layout: self.trace_layout,
+            report_layout: self.report_layout.clone(),
+            redactor: self.redactor.clone(),
+            #[cfg(feature = "capture-spantrace")]
+            span_filter: self.span_filter.clone(),
             #[cfg(feature = "issue-url")]
             issue_url: self.issue_url.clone(),
             #[cfg(feature = "issue-url")]
+            issue_tracker: self.issue_tracker.clone(),
+            #[cfg(feature = "issue-url")]
+            issue_url_max_length: self.issue_url_max_length,
+            #[cfg(feature = "issue-url")]
+            issue_template: self.issue_template.clone(),
+            #[cfg(feature = "issue-url")]
+            issue_bundle: self.issue_bundle.clone(),
+            #[cfg(feature = "issue-url")]
+            issue_prompt: self.issue_prompt.clone(),
+            #[cfg(feature = "issue-url")]
             issue_metadata: self.issue_metadata.clone(),
             #[cfg(feature = "issue-url")]
             issue_filter: self.issue_filter.clone(),
+            #[cfg(feature = "crash-report")]
+            crash_reporter: self.crash_reporter.clone(),
             theme: self.theme,
             #[cfg(feature = "track-caller")]
             location: None,
@@ -1075,27 +2550,116 @@ pub(crate) struct BacktraceFormatter<'a> {
     pub(crate) filters: &'a [Box<FilterCallback>],
     pub(crate) inner: &'a backtrace::Backtrace,
     pub(crate) theme: Theme,
+    #[cfg(feature = "capture-spantrace")]
+    pub(crate) span_trace: Option<&'a tracing_error::SpanTrace>,
+    #[cfg(feature = "capture-spantrace")]
+    pub(crate) span_filter: &'a SpanFilter,
+    pub(crate) layout: TraceLayout,
+}
+
+impl<'a> BacktraceFormatter<'a> {
+    /// Merge the given span trace into the backtrace, unless configured to
+    /// print them separately
+    #[cfg(feature = "capture-spantrace")]
+    pub(crate) fn with_span_trace(
+        mut self,
+        span_trace: Option<&'a tracing_error::SpanTrace>,
+    ) -> Self {
+        self.span_trace = span_trace
+            .filter(|span_trace| span_trace.status() == tracing_error::SpanTraceStatus::CAPTURED);
+        self
+    }
+
+    /// Whether a span trace is merged into this backtrace
+    #[cfg(feature = "capture-spantrace")]
+    pub(crate) fn is_merged(&self) -> bool {
+        self.layout != TraceLayout::Separate && self.span_trace.is_some()
+    }
 }
 
 impl fmt::Display for BacktraceFormatter<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:━^80}", " BACKTRACE ")?;
-
         // Collect frame info.
-        let frames: Vec<_> = self
-            .inner
-            .frames()
+        let frames = Frame::collect(self.inner);
+
+        #[cfg(feature = "capture-spantrace")]
+        let spans = match self.span_trace {
+            Some(span_trace) if self.is_merged() => self.span_filter.collect(span_trace).0,
+            _ => Vec::new(),
+        };
+
+        #[cfg(not(feature = "capture-spantrace"))]
+        let spans = Vec::new();
+
+        // Without symbols the styled backtrace is a wall of `<unknown>`s, so
+        // print the addresses needed to symbolize it later instead. Only the
+        // executable counts, system libraries are often stripped.
+        #[cfg(feature = "raw-backtrace")]
+        if is_stripped(&frames) {
+            // There are no frames to merge the span trace into.
+            #[cfg(feature = "capture-spantrace")]
+            if let Some(span_trace) = self.span_trace.filter(|_| self.is_merged()) {
+                write!(
+                    f,
+                    "{}\n\n",
+                    SpanTraceSection(span_trace, self.span_filter, self.theme)
+                )?;
+            }
+
+            write!(f, "{:━^80}", " RAW BACKTRACE ")?;
+            writeln!(
+                f,
+                "\nSymbols are unavailable, use `color_eyre::symbolize` with the unstripped binary to resolve them."
+            )?;
+            return write!(
+                f,
+                "{}",
+                crate::symbolize::RawBacktrace::from_frames(&frames)
+            );
+        }
+
+        FramesFormatter {
+            filters: self.filters,
+            frames: &frames,
+            theme: self.theme,
+            spans: &spans,
+            layout: self.layout,
+        }
+        .fmt(f)
+    }
+}
+
+/// Whether the main executable appears in `frames` without any symbols
+#[cfg(feature = "raw-backtrace")]
+fn is_stripped(frames: &[Frame]) -> bool {
+    let exe = match env::current_exe() {
+        Ok(exe) => Some(exe),
+        Err(_) => return false,
+    };
+
+    frames.iter().any(|frame| frame.module == exe)
+        && !frames
             .iter()
-            .flat_map(|frame| frame.symbols())
-            .zip(1usize..)
-            .map(|(sym, n)| Frame {
-                name: sym.name().map(|x| x.to_string()),
-                lineno: sym.lineno(),
-                filename: sym.filename().map(|x| x.into()),
-                n,
-            })
-            .collect();
+            .any(|frame| frame.module == exe && frame.name.is_some())
+}
+
+pub(crate) struct FramesFormatter<'a> {
+    pub(crate) filters: &'a [Box<FilterCallback>],
+    pub(crate) frames: &'a [Frame],
+    pub(crate) theme: Theme,
+    pub(crate) spans: &'a [SpanFrame],
+    pub(crate) layout: TraceLayout,
+}
 
+impl fmt::Display for FramesFormatter<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.spans.is_empty() {
+            write!(f, "{:━^80}", " BACKTRACE ")?;
+        } else {
+            write!(f, "{:━^80}", " EXECUTION TRACE ")?;
+        }
+
+        let frames = self.frames;
         let mut filtered_frames = frames.iter().collect();
         match env::var("COLORBT_SHOW_HIDDEN").ok().as_deref() {
             Some("1") | Some("on") | Some("y") => (),
@@ -1106,7 +2670,7 @@ impl fmt::Display for BacktraceFormatter<'_> {
             }
         }
 
-        if filtered_frames.is_empty() {
+        if filtered_frames.is_empty() && self.spans.is_empty() {
             // TODO: Would probably look better centered.
             return write!(f, "\n<empty backtrace>");
         }
@@ -1116,6 +2680,29 @@ impl fmt::Display for BacktraceFormatter<'_> {
         // Don't let filters mess with the order.
         filtered_frames.sort_by_key(|x| x.n);
 
+        // Place each span before the frame it was entered in, and the spans
+        // without a frame before the frame of the next placed span.
+        let mut placed = vec![None; filtered_frames.len()];
+        let mut preceding = vec![Vec::new(); filtered_frames.len() + 1];
+        let mut pending = Vec::new();
+        let mut cursor = 0;
+        for span in self.spans {
+            let position = filtered_frames[cursor..]
+                .iter()
+                .position(|frame| span.matches(frame));
+
+            match position {
+                Some(position) => {
+                    let i = cursor + position;
+                    preceding[i].append(&mut pending);
+                    placed[i] = Some(span);
+                    cursor = i + 1;
+                }
+                None => pending.push(span),
+            }
+        }
+        preceding[filtered_frames.len()].append(&mut pending);
+
         let mut buf = String::new();
 
         macro_rules! print_hidden {
@@ -1139,21 +2726,58 @@ impl fmt::Display for BacktraceFormatter<'_> {
         }
 
         let mut last_n = 0;
-        for frame in &filtered_frames {
-            let frame_delta = frame.n - last_n - 1;
+        for (i, frame) in filtered_frames.iter().enumerate() {
+            // Inlined symbols are nested under their physical frame, unless
+            // a filter removed it.
+            let nested = frame.inlined && frame.n == last_n;
+            let frame_delta = frame.n.saturating_sub(last_n + 1);
             if frame_delta != 0 {
                 print_hidden!(frame_delta);
             }
-            write!(&mut separated.ready(), "{}", StyledFrame(frame, self.theme))?;
+
+            for span in &preceding[i] {
+                write!(
+                    &mut separated.ready(),
+                    "  ⋄ {}",
+                    StyledSpan(span, self.theme)
+                )?;
+            }
+
+            let merged = match placed[i] {
+                Some(span) if self.layout == TraceLayout::Deduplicated => Some(span),
+                Some(span) => {
+                    write!(
+                        &mut separated.ready(),
+                        "  ⋄ {}",
+                        StyledSpan(span, self.theme)
+                    )?;
+                    None
+                }
+                None => None,
+            };
+
+            write!(
+                &mut separated.ready(),
+                "{}",
+                StyledFrame(frame, self.theme, merged, nested)
+            )?;
             last_n = frame.n;
         }
 
-        let last_filtered_n = filtered_frames.last().unwrap().n;
-        let last_unfiltered_n = frames.last().unwrap().n;
+        let last_filtered_n = filtered_frames.last().map_or(0, |frame| frame.n);
+        let last_unfiltered_n = frames.last().map_or(0, |frame| frame.n);
         if last_filtered_n < last_unfiltered_n {
             print_hidden!(last_unfiltered_n - last_filtered_n);
         }
 
+        for span in &preceding[filtered_frames.len()] {
+            write!(
+                &mut separated.ready(),
+                "  ⋄ {}",
+                StyledSpan(span, self.theme)
+            )?;
+        }
+
         Ok(())
     }
 }
@@ -1187,4 +2811,5 @@ pub type FilterCallback = dyn Fn(&mut Vec<&Frame>) + Send + Sync + 'static;
 /// Callback for filtering issue url generation in error reports
 #[cfg(feature = "issue-url")]
 #[cfg_attr(docsrs, doc(cfg(feature = "issue-url")))]
-pub type IssueFilterCallback = dyn Fn(crate::ErrorKind<'_>) -> bool + Send + Sync + 'static;
+pub type IssueFilterCallback =
+    dyn Fn(&crate::issue::IssueContext<'_>) -> crate::issue::IssueDecision + Send + Sync + 'static;
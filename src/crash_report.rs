@@ -0,0 +1,802 @@
+//! Sending crash reports to a collection endpoint
+//!
+//! A `CrashReporter` configured with `HookBuilder::crash_reporter` POSTs a
+//! JSON report of every panic to the endpoint, and [`send`] does the same for
+//! `Report`s. Reports that can't be delivered are spooled to disk and sent
+//! again the next time the hook is installed.
+//!
+//! # Report format
+//!
+//! Reports are JSON objects with the following fields:
+//!
+//! - `kind`: `"panic"` or `"error"`
+//! - `message`: the panic payload, or the message of the error
+//! - `causes`: the messages of the sources of the error, outermost first
+//! - `fields`: the fields attached with `Section::field`, with their values
+//!   as JSON strings, numbers or booleans
+//! - `code`: the error code set with `Section::code`, or `null`
+//! - `docs_url`: the documentation link of the error code, or `null`
+//! - `location`: the panic or `track_caller` location, or `null`
+//! - `fingerprint`: the `Fingerprint` of the report
+//! - `timestamp`: the unix time the report was created at, in seconds
+//! - `environment`: the entries of the `Environment` configured with
+//!   `HookBuilder::environment_metadata`, if any
+//! - `backtrace`: the frames of the filtered backtrace, with their
+//!   `function`, `file` and `line`, or `null` if it wasn't captured
+//! - `spans`: the spans of the `SpanTrace`, innermost first, with their
+//!   `target`, `name`, `fields`, `file` and `line`, or `null` if it wasn't
+//!   captured. The span field rules and limit of the hook are applied
+//!
+//! All strings are redacted with the rules of the hook.
+use crate::{
+    config::{filter_frames, FilterCallback, Frame},
+    environment::Environment,
+    fingerprint::Fingerprint,
+    redact::Redactor,
+    section::FieldValue,
+};
+use std::{
+    fmt::Write as _,
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpStream, ToSocketAddrs},
+    panic::Location,
+    path::{Path, PathBuf},
+    sync::mpsc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// A client that delivers crash reports to an endpoint
+///
+/// # Examples
+///
+/// ```rust
+/// use color_eyre::crash_report::{CrashReporter, Transport};
+///
+/// /// Hands reports to the application's own HTTP client
+/// struct Client;
+///
+/// impl Transport for Client {
+///     fn send(&self, url: &str, body: &str) -> std::io::Result<()> {
+///         println!("POST {}\n{}", url, body);
+///         Ok(())
+///     }
+/// }
+///
+/// color_eyre::config::HookBuilder::default()
+///     .crash_reporter(CrashReporter::with_transport(
+///         "https://crashes.example.com/report",
+///         Client,
+///     ))
+///     .install()
+///     .unwrap();
+/// ```
+pub trait Transport: Send + Sync + 'static {
+    /// Send the JSON encoded report in `body` to `url`
+    ///
+    /// Errors cause the report to be retried, and spooled if it still can't
+    /// be sent.
+    fn send(&self, url: &str, body: &str) -> io::Result<()>;
+
+    /// Send the report like `send`, giving up after about `timeout`
+    ///
+    /// Used by the panic hook, which makes a single attempt so the panicking
+    /// application isn't held up. The default ignores the timeout and calls
+    /// `send`.
+    fn send_with_timeout(&self, url: &str, body: &str, timeout: Duration) -> io::Result<()> {
+        let _ = timeout;
+        self.send(url, body)
+    }
+}
+
+/// A minimal HTTP/1.1 client for `http://` urls, used by
+/// `CrashReporter::new`
+///
+/// Any `2xx` response counts as delivered. Use a custom `Transport` with
+/// `CrashReporter::with_transport` for `https://` urls, proxies or
+/// authentication.
+#[derive(Debug, Clone)]
+pub struct HttpTransport {
+    timeout: Duration,
+}
+
+impl HttpTransport {
+    /// Create a transport with a timeout of 5 seconds
+    pub fn new() -> Self {
+        HttpTransport {
+            timeout: Duration::from_secs(5),
+        }
+    }
+
+    /// Set the timeout for connecting, and for each read and write
+    ///
+    /// The connection timeout covers resolving the host name and trying each
+    /// of its addresses together.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+impl Default for HttpTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Transport for HttpTransport {
+    fn send(&self, url: &str, body: &str) -> io::Result<()> {
+        self.post(url, body, self.timeout)
+    }
+
+    fn send_with_timeout(&self, url: &str, body: &str, timeout: Duration) -> io::Result<()> {
+        self.post(url, body, self.timeout.min(timeout))
+    }
+}
+
+impl HttpTransport {
+    fn post(&self, url: &str, body: &str, timeout: Duration) -> io::Result<()> {
+        let (authority, path) = split_http_url(url)?;
+        let mut stream = connect(authority, timeout)?;
+
+        stream.set_read_timeout(Some(timeout))?;
+        stream.set_write_timeout(Some(timeout))?;
+
+        write!(
+            stream,
+            "POST {} HTTP/1.1\r\n\
+             Host: {}\r\n\
+             User-Agent: color-eyre/{}\r\n\
+             Content-Type: application/json\r\n\
+             Content-Length: {}\r\n\
+             Connection: close\r\n\
+             \r\n",
+            path,
+            authority,
+            env!("CARGO_PKG_VERSION"),
+            body.len()
+        )?;
+        stream.write_all(body.as_bytes())?;
+        stream.flush()?;
+
+        let mut status = String::new();
+        BufReader::new(stream).read_line(&mut status)?;
+
+        let code = status
+            .split_whitespace()
+            .nth(1)
+            .and_then(|code| code.parse::<u16>().ok());
+
+        match code {
+            Some(200..=299) => Ok(()),
+            Some(_) => Err(io::Error::other(format!(
+                "the server responded with `{}`",
+                status.trim_end()
+            ))),
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the server sent an invalid response",
+            )),
+        }
+    }
+}
+
+/// Connect to `authority` before `timeout` runs out, counting the name
+/// resolution and the attempts to connect to each of its addresses
+fn connect(authority: &str, timeout: Duration) -> io::Result<TcpStream> {
+    let deadline = Instant::now() + timeout;
+
+    // The colons of IPv6 addresses are inside brackets.
+    let has_port = matches!(authority.rfind(':'), Some(i) if !authority[i..].contains(']'));
+    let addr = if has_port {
+        authority.to_string()
+    } else {
+        format!("{}:80", authority)
+    };
+
+    // Name resolution blocks without a timeout, so it runs on its own thread
+    // which is left behind if it takes too long.
+    let (sender, receiver) = mpsc::channel();
+    std::thread::Builder::new()
+        .name("color-eyre-resolve".into())
+        .spawn(move || {
+            let addrs = addr
+                .to_socket_addrs()
+                .map(|addrs| addrs.collect::<Vec<_>>());
+            let _ = sender.send(addrs);
+        })?;
+    let addrs = receiver
+        .recv_timeout(remaining(deadline)?)
+        .map_err(|_| timed_out("resolving the host"))??;
+
+    let mut last_error = None;
+    for addr in addrs {
+        match TcpStream::connect_timeout(&addr, remaining(deadline)?) {
+            Ok(stream) => return Ok(stream),
+            Err(error) => last_error = Some(error),
+        }
+    }
+
+    Err(last_error
+        .unwrap_or_else(|| io::Error::new(io::ErrorKind::NotFound, "the host has no addresses")))
+}
+
+/// The time left until `deadline`, or an error if it has passed
+fn remaining(deadline: Instant) -> io::Result<Duration> {
+    match deadline.checked_duration_since(Instant::now()) {
+        Some(remaining) if remaining > Duration::from_millis(0) => Ok(remaining),
+        _ => Err(timed_out("connecting")),
+    }
+}
+
+fn timed_out(step: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::TimedOut, format!("timed out {}", step))
+}
+
+/// Split an `http://` url into its authority and path
+fn split_http_url(url: &str) -> io::Result<(&str, &str)> {
+    let rest = url.strip_prefix("http://").ok_or_else(|| {
+        let message = if url.starts_with("https://") {
+            "`HttpTransport` doesn't support `https://` urls, use \
+             `CrashReporter::with_transport` with a transport that supports TLS"
+        } else {
+            "`HttpTransport` only supports `http://` urls"
+        };
+        io::Error::new(io::ErrorKind::InvalidInput, message)
+    })?;
+
+    Ok(match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    })
+}
+
+/// How a crash report was delivered
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Delivery {
+    /// The report was sent to the endpoint
+    Sent,
+    /// The report couldn't be sent, and was saved to the given file to be
+    /// sent later
+    Spooled(PathBuf),
+}
+
+/// Sends crash reports to an endpoint, retrying and spooling them to disk
+/// when it can't be reached
+///
+/// # Details
+///
+/// Reports of panics are attempted once, limited to the `panic_timeout`, so
+/// the panicking application isn't held up. Reports sent with [`send`] are
+/// attempted once, and then retried up to `retries` times with a delay that
+/// doubles after every attempt.
+///
+/// If a report can't be sent and a spool directory is set, it is saved
+/// there, and sent again the next time the hook is installed with
+/// `HookBuilder::install`, in a background thread. Without a spool directory
+/// undeliverable reports are dropped.
+///
+/// The report is printed as usual, followed by a line saying whether it was
+/// sent or spooled.
+///
+/// # Examples
+///
+/// ```rust
+/// use color_eyre::crash_report::{CrashReporter, HttpTransport};
+/// use std::time::Duration;
+///
+/// let transport = HttpTransport::new().timeout(Duration::from_secs(2));
+///
+/// color_eyre::config::HookBuilder::default()
+///     .environment_metadata(color_eyre::environment!())
+///     .crash_reporter(
+///         CrashReporter::with_transport("http://crashes.internal:8080/report", transport)
+///             .retries(3)
+///             .panic_timeout(Duration::from_millis(500))
+///             .spool_dir(std::env::temp_dir().join("my-app-crashes")),
+///     )
+///     .install()
+///     .unwrap();
+/// ```
+pub struct CrashReporter {
+    url: String,
+    transport: Box<dyn Transport>,
+    retries: u32,
+    retry_delay: Duration,
+    panic_timeout: Duration,
+    spool_dir: Option<PathBuf>,
+    pub(crate) environment: Option<Environment>,
+}
+
+impl CrashReporter {
+    /// Send reports to the given `http://` url with an `HttpTransport`,
+    /// retrying twice
+    ///
+    /// Fails if the url isn't an `http://` url, use `with_transport` for
+    /// other urls.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use color_eyre::crash_report::CrashReporter;
+    ///
+    /// assert!(CrashReporter::new("http://crashes.internal:8080/report").is_ok());
+    /// assert!(CrashReporter::new("https://crashes.example.com/report").is_err());
+    /// ```
+    pub fn new(url: impl Into<String>) -> io::Result<Self> {
+        let url = url.into();
+        split_http_url(&url)?;
+
+        Ok(Self::with_transport(url, HttpTransport::new()))
+    }
+
+    /// Send reports to the given url with the given transport, retrying
+    /// twice
+    pub fn with_transport(url: impl Into<String>, transport: impl Transport) -> Self {
+        CrashReporter {
+            url: url.into(),
+            transport: Box::new(transport),
+            retries: 2,
+            retry_delay: Duration::from_millis(250),
+            panic_timeout: Duration::from_secs(1),
+            spool_dir: None,
+            environment: None,
+        }
+    }
+
+    /// Set how many times a report sent with [`send`] is retried before it
+    /// is spooled
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Set the delay before the first retry, 250 milliseconds by default
+    pub fn retry_delay(mut self, delay: Duration) -> Self {
+        self.retry_delay = delay;
+        self
+    }
+
+    /// Set the timeout of the single attempt to send a panic report, 1
+    /// second by default
+    pub fn panic_timeout(mut self, timeout: Duration) -> Self {
+        self.panic_timeout = timeout;
+        self
+    }
+
+    /// Spool reports that can't be sent to the given directory, which is
+    /// created if it doesn't exist
+    ///
+    /// The directory should only be used by one application, as the spooled
+    /// reports are sent to the url of whichever reporter finds them.
+    pub fn spool_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.spool_dir = Some(dir.into());
+        self
+    }
+
+    /// Send the reports in the spool directory, oldest first, and return how
+    /// many were sent
+    ///
+    /// Stops at the first report the transport fails to send, leaving it and
+    /// the remaining ones for the next call. Spooled reports are only
+    /// attempted once per call. Files that can't be read or don't contain a
+    /// JSON object are renamed with a `.corrupt` extension and skipped.
+    pub fn send_spooled(&self) -> io::Result<usize> {
+        let dir = match &self.spool_dir {
+            Some(dir) => dir,
+            None => return Ok(0),
+        };
+
+        let entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(0),
+            Err(error) => return Err(error),
+        };
+
+        let mut paths: Vec<_> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| is_spooled_report(path))
+            .collect();
+        // The names start with the timestamp, so this sorts them by age.
+        paths.sort();
+
+        let mut sent = 0;
+        for path in paths {
+            let body = match std::fs::read_to_string(&path) {
+                Ok(body) if is_json_object(&body) => body,
+                _ => {
+                    // Keep the file around for inspection, but out of the way
+                    // of the next call.
+                    let _ = std::fs::rename(&path, path.with_extension("json.corrupt"));
+                    continue;
+                }
+            };
+
+            if self.transport.send(&self.url, &body).is_err() {
+                break;
+            }
+
+            std::fs::remove_file(&path)?;
+            sent += 1;
+        }
+
+        Ok(sent)
+    }
+
+    /// Send a report, retrying and spooling it if needed
+    pub(crate) fn deliver(&self, report: &CrashReport<'_>) -> io::Result<Delivery> {
+        let body = report.to_json(self.environment.as_ref());
+
+        let mut delay = self.retry_delay;
+        let mut attempt = 0;
+        let error = loop {
+            match self.transport.send(&self.url, &body) {
+                Ok(()) => return Ok(Delivery::Sent),
+                Err(error) if attempt >= self.retries => break error,
+                Err(_) => {
+                    std::thread::sleep(delay);
+                    delay *= 2;
+                    attempt += 1;
+                }
+            }
+        };
+
+        self.spool(report, body, error)
+    }
+
+    /// Send the report of a panic with a single attempt limited to the
+    /// panic timeout, spooling it if it can't be sent
+    pub(crate) fn deliver_panic(&self, report: &CrashReport<'_>) -> io::Result<Delivery> {
+        let body = report.to_json(self.environment.as_ref());
+
+        match self
+            .transport
+            .send_with_timeout(&self.url, &body, self.panic_timeout)
+        {
+            Ok(()) => Ok(Delivery::Sent),
+            Err(error) => self.spool(report, body, error),
+        }
+    }
+
+    /// Save a report that couldn't be sent because of `error` to the spool
+    /// directory, or return the error if there is none
+    fn spool(
+        &self,
+        report: &CrashReport<'_>,
+        body: String,
+        error: io::Error,
+    ) -> io::Result<Delivery> {
+        let dir = match &self.spool_dir {
+            Some(dir) => dir,
+            None => return Err(error),
+        };
+
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join(format!(
+            "{:020}-{}.json",
+            report.timestamp_nanos, report.fingerprint
+        ));
+        std::fs::write(&path, body)?;
+
+        Ok(Delivery::Spooled(path))
+    }
+
+    /// A one line summary of the outcome of `deliver`, printed after reports
+    pub(crate) fn describe(&self, delivery: &io::Result<Delivery>) -> String {
+        match delivery {
+            Ok(Delivery::Sent) => "A crash report was sent.".to_string(),
+            Ok(Delivery::Spooled(path)) => format!(
+                "A crash report could not be sent and was saved to: {}",
+                path.display()
+            ),
+            Err(error) => format!("Failed to send a crash report: {}", error),
+        }
+    }
+}
+
+impl std::fmt::Debug for CrashReporter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CrashReporter")
+            .field("url", &self.url)
+            .field("retries", &self.retries)
+            .field("retry_delay", &self.retry_delay)
+            .field("panic_timeout", &self.panic_timeout)
+            .field("spool_dir", &self.spool_dir)
+            .finish()
+    }
+}
+
+/// Whether `body` looks like a whole JSON object, rather than a file that was
+/// cut short or overwritten
+fn is_json_object(body: &str) -> bool {
+    let body = body.trim();
+    body.starts_with('{') && body.ends_with('}')
+}
+
+fn is_spooled_report(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "json")
+        && path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with(|c: char| c.is_ascii_digit()))
+}
+
+/// Send a crash report for `report` with the `CrashReporter` configured with
+/// `HookBuilder::crash_reporter`
+///
+/// Returns `None` if no crash reporter is configured, or the report was not
+/// created with color-eyre's handler.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use color_eyre::{crash_report::CrashReporter, eyre::eyre};
+///
+/// color_eyre::config::HookBuilder::default()
+///     .crash_reporter(
+///         CrashReporter::new("http://crashes.internal:8080/report")
+///             .unwrap()
+///             .retries(0)
+///             .spool_dir(std::env::temp_dir().join("my-app-crashes")),
+///     )
+///     .install()
+///     .unwrap();
+///
+/// let report = eyre!("failed to load the config");
+/// eprintln!("Error: {:?}", report);
+///
+/// match color_eyre::crash_report::send(&report) {
+///     Ok(delivery) => println!("{:?}", delivery),
+///     Err(error) => eprintln!("Failed to send a crash report: {}", error),
+/// }
+/// ```
+pub fn send(report: &eyre::Report) -> io::Result<Option<Delivery>> {
+    match report.handler().downcast_ref::<crate::Handler>() {
+        Some(handler) => handler.send_crash_report(&**report),
+        None => Ok(None),
+    }
+}
+
+/// The contents of a crash report
+pub(crate) struct CrashReport<'a> {
+    pub(crate) kind: &'static str,
+    pub(crate) message: String,
+    pub(crate) causes: Vec<String>,
+    pub(crate) fields: Vec<(&'a str, &'a FieldValue)>,
+    pub(crate) code: Option<&'a str>,
+    pub(crate) docs_url: Option<String>,
+    pub(crate) location: Option<&'a Location<'a>>,
+    pub(crate) fingerprint: Fingerprint,
+    pub(crate) backtrace: Option<&'a backtrace::Backtrace>,
+    #[cfg(feature = "capture-spantrace")]
+    pub(crate) spans: Option<Vec<crate::config::SpanFrame>>,
+    pub(crate) filters: &'a [Box<FilterCallback>],
+    pub(crate) redactor: &'a Redactor,
+    timestamp_nanos: u128,
+}
+
+impl<'a> CrashReport<'a> {
+    pub(crate) fn new(
+        kind: &'static str,
+        message: String,
+        fingerprint: Fingerprint,
+        filters: &'a [Box<FilterCallback>],
+        redactor: &'a Redactor,
+    ) -> Self {
+        CrashReport {
+            kind,
+            message,
+            causes: Vec::new(),
+            fields: Vec::new(),
+            code: None,
+            docs_url: None,
+            location: None,
+            fingerprint,
+            backtrace: None,
+            #[cfg(feature = "capture-spantrace")]
+            spans: None,
+            filters,
+            redactor,
+            timestamp_nanos: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |time| time.as_nanos()),
+        }
+    }
+
+    fn to_json(&self, environment: Option<&Environment>) -> String {
+        let mut json = String::from("{");
+
+        json.push_str("\"kind\":");
+        self.push_str(&mut json, self.kind);
+        json.push_str(",\"message\":");
+        self.push_str(&mut json, &self.message);
+
+        json.push_str(",\"causes\":[");
+        for (n, cause) in self.causes.iter().enumerate() {
+            if n > 0 {
+                json.push(',');
+            }
+            self.push_str(&mut json, cause);
+        }
+        json.push(']');
+
+        json.push_str(",\"fields\":{");
+        for (n, (key, value)) in self.fields.iter().enumerate() {
+            if n > 0 {
+                json.push(',');
+            }
+            push_json_str(&mut json, key);
+            json.push(':');
+            match value {
+                FieldValue::String(value) => self.push_str(&mut json, value),
+                FieldValue::Float(value) if !value.is_finite() => json.push_str("null"),
+                value => {
+                    let _ = write!(json, "{}", value);
+                }
+            }
+        }
+        json.push('}');
+
+        json.push_str(",\"code\":");
+        match self.code {
+            Some(code) => self.push_str(&mut json, code),
+            None => json.push_str("null"),
+        }
+
+        json.push_str(",\"docs_url\":");
+        match &self.docs_url {
+            Some(docs_url) => self.push_str(&mut json, docs_url),
+            None => json.push_str("null"),
+        }
+
+        json.push_str(",\"location\":");
+        match self.location {
+            Some(location) => self.push_str(&mut json, &location.to_string()),
+            None => json.push_str("null"),
+        }
+
+        json.push_str(",\"fingerprint\":");
+        self.push_str(&mut json, &self.fingerprint.to_string());
+        let _ = write!(
+            json,
+            ",\"timestamp\":{}",
+            self.timestamp_nanos / 1_000_000_000
+        );
+
+        if let Some(environment) = environment {
+            json.push_str(",\"environment\":{");
+            for (n, (key, value)) in environment.entries().iter().enumerate() {
+                if n > 0 {
+                    json.push(',');
+                }
+                push_json_str(&mut json, key);
+                json.push(':');
+                self.push_str(&mut json, value);
+            }
+            json.push('}');
+        }
+
+        json.push_str(",\"backtrace\":");
+        match self.backtrace {
+            Some(backtrace) => self.push_frames(&mut json, backtrace),
+            None => json.push_str("null"),
+        }
+
+        json.push_str(",\"spans\":");
+        #[cfg(feature = "capture-spantrace")]
+        match &self.spans {
+            Some(spans) => self.push_spans(&mut json, spans),
+            None => json.push_str("null"),
+        }
+        #[cfg(not(feature = "capture-spantrace"))]
+        json.push_str("null");
+
+        json.push('}');
+        json
+    }
+
+    /// Set the spans of the report to those of `span_trace` with the rules
+    /// of `filter` applied
+    #[cfg(feature = "capture-spantrace")]
+    pub(crate) fn set_span_trace(
+        &mut self,
+        span_trace: Option<&tracing_error::SpanTrace>,
+        filter: &crate::config::SpanFilter,
+    ) {
+        self.spans = span_trace
+            .filter(|span_trace| span_trace.status() == tracing_error::SpanTraceStatus::CAPTURED)
+            .map(|span_trace| filter.collect(span_trace).0);
+    }
+
+    #[cfg(feature = "capture-spantrace")]
+    fn push_spans(&self, json: &mut String, spans: &[crate::config::SpanFrame]) {
+        json.push('[');
+        for (n, span) in spans.iter().enumerate() {
+            if n > 0 {
+                json.push(',');
+            }
+
+            json.push_str("{\"target\":");
+            self.push_str(json, span.target);
+            json.push_str(",\"name\":");
+            self.push_str(json, span.name);
+            json.push_str(",\"fields\":");
+            self.push_str(json, &span.fields);
+
+            json.push_str(",\"file\":");
+            match span.file {
+                Some(file) => self.push_str(json, file),
+                None => json.push_str("null"),
+            }
+
+            json.push_str(",\"line\":");
+            match span.line {
+                Some(line) => {
+                    let _ = write!(json, "{}", line);
+                }
+                None => json.push_str("null"),
+            }
+            json.push('}');
+        }
+        json.push(']');
+    }
+
+    fn push_frames(&self, json: &mut String, backtrace: &backtrace::Backtrace) {
+        let frames = Frame::collect(backtrace);
+        let filtered = filter_frames(&frames, self.filters);
+
+        json.push('[');
+        for (n, frame) in filtered.iter().enumerate() {
+            if n > 0 {
+                json.push(',');
+            }
+
+            json.push_str("{\"function\":");
+            match &frame.name {
+                Some(name) => self.push_str(json, crate::config::split_hash(name).0),
+                None => json.push_str("null"),
+            }
+
+            json.push_str(",\"file\":");
+            match &frame.filename {
+                Some(file) => self.push_str(json, &file.display().to_string()),
+                None => json.push_str("null"),
+            }
+
+            json.push_str(",\"line\":");
+            match frame.lineno {
+                Some(line) => {
+                    let _ = write!(json, "{}", line);
+                }
+                None => json.push_str("null"),
+            }
+            json.push('}');
+        }
+        json.push(']');
+    }
+
+    /// Push a redacted JSON string
+    fn push_str(&self, json: &mut String, value: &str) {
+        let mut value = value.to_string();
+        self.redactor.redact(&mut value);
+        push_json_str(json, &value);
+    }
+}
+
+fn push_json_str(json: &mut String, value: &str) {
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
@@ -0,0 +1,238 @@
+//! Automatic collection of information about the environment a report was
+//! created in
+use std::fmt;
+
+/// Information about the application, the toolchain it was built with, and
+/// the system it is running on
+///
+/// # Details
+///
+/// Collects the following entries:
+///
+/// - `crate` and `version`: the name and version of the application
+/// - `rustc` and `target`: the compiler version and target triple the
+///   application was built with
+/// - `os`: the operating system and kernel release
+/// - `arch`: the CPU architecture
+/// - `args`: the command line arguments
+/// - `current dir`: the working directory at the time of the report
+/// - `features`: the enabled cargo features of the application
+///
+/// Use the [`environment!`] macro to create it with the name, version and
+/// features of the calling crate, and `HookBuilder::environment_metadata` to
+/// add it to generated issues and panic reports.
+///
+/// The command line arguments and working directory can contain secrets or
+/// personal information. They are subject to the redaction rules of the
+/// hook, and can be left out with `args` and `current_dir`.
+///
+/// # Examples
+///
+/// ```rust
+/// color_eyre::config::HookBuilder::default()
+///     .environment_metadata(color_eyre::environment!(features: ["tls"]).args(false))
+///     .install()
+///     .unwrap();
+/// ```
+///
+/// [`environment!`]: ../macro.environment.html
+#[derive(Debug, Clone)]
+pub struct Environment {
+    name: String,
+    version: String,
+    features: Vec<&'static str>,
+    os: String,
+    args: Option<String>,
+    current_dir: bool,
+}
+
+impl Environment {
+    /// Collect the environment of the application with the given name and
+    /// version
+    ///
+    /// Prefer the [`environment!`] macro, which fills these in from cargo.
+    ///
+    /// [`environment!`]: ../macro.environment.html
+    pub fn new(name: impl Into<String>, version: impl Into<String>) -> Self {
+        Environment {
+            name: name.into(),
+            version: version.into(),
+            features: Vec::new(),
+            os: os(),
+            args: Some(command_line()),
+            current_dir: true,
+        }
+    }
+
+    /// Add an enabled cargo feature
+    pub fn feature(mut self, feature: &'static str) -> Self {
+        self.features.push(feature);
+        self
+    }
+
+    /// Configures whether the command line arguments are included
+    pub fn args(mut self, include: bool) -> Self {
+        self.args = if include { Some(command_line()) } else { None };
+        self
+    }
+
+    /// Configures whether the working directory is included
+    pub fn current_dir(mut self, include: bool) -> Self {
+        self.current_dir = include;
+        self
+    }
+
+    /// The collected entries as key value pairs
+    ///
+    /// The working directory is read when this is called, all other entries
+    /// when the `Environment` is created.
+    pub fn entries(&self) -> Vec<(&'static str, String)> {
+        let mut entries = vec![
+            ("crate", self.name.clone()),
+            ("version", self.version.clone()),
+            ("rustc", env!("COLOR_EYRE_RUSTC_VERSION").to_string()),
+            ("target", env!("COLOR_EYRE_TARGET").to_string()),
+            ("os", self.os.clone()),
+            ("arch", std::env::consts::ARCH.to_string()),
+        ];
+
+        if let Some(args) = &self.args {
+            entries.push(("args", args.clone()));
+        }
+
+        if self.current_dir {
+            if let Ok(dir) = std::env::current_dir() {
+                entries.push(("current dir", dir.display().to_string()));
+            }
+        }
+
+        if !self.features.is_empty() {
+            entries.push(("features", self.features.join(", ")));
+        }
+
+        entries
+    }
+
+    /// The entries as issue metadata, with the working directory read when
+    /// it is displayed
+    #[cfg(feature = "issue-url")]
+    pub(crate) fn metadata(&self) -> Vec<(String, Box<dyn fmt::Display + Send + Sync + 'static>)> {
+        self.clone()
+            .current_dir(false)
+            .entries()
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), Box::new(value) as _))
+            .chain(
+                Some(("current dir".to_string(), Box::new(CurrentDir) as _))
+                    .filter(|_| self.current_dir),
+            )
+            .collect()
+    }
+}
+
+impl fmt::Display for Environment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entries = self.entries();
+        let width = entries.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+
+        for (n, (key, value)) in entries.iter().enumerate() {
+            if n > 0 {
+                writeln!(f)?;
+            }
+
+            write!(f, "{:>width$}: {}", key, value, width = width)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The working directory, read when it is displayed
+#[cfg(feature = "issue-url")]
+struct CurrentDir;
+
+#[cfg(feature = "issue-url")]
+impl fmt::Display for CurrentDir {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match std::env::current_dir() {
+            Ok(dir) => write!(f, "{}", dir.display()),
+            Err(_) => f.write_str("<unknown>"),
+        }
+    }
+}
+
+/// The operating system and, where known, its kernel release
+pub(crate) fn os() -> String {
+    match kernel_release() {
+        Some(release) => format!("{} {}", std::env::consts::OS, release),
+        None => std::env::consts::OS.to_string(),
+    }
+}
+
+/// The command line, with arguments containing whitespace quoted
+fn command_line() -> String {
+    std::env::args_os()
+        .map(|arg| {
+            let arg = arg.to_string_lossy().into_owned();
+            if arg.is_empty() || arg.contains(char::is_whitespace) {
+                format!("{:?}", arg)
+            } else {
+                arg
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(unix)]
+fn kernel_release() -> Option<String> {
+    let mut uts = std::mem::MaybeUninit::<libc::utsname>::uninit();
+
+    // SAFETY: `uname` fills in the struct when it returns 0.
+    let uts = unsafe {
+        if libc::uname(uts.as_mut_ptr()) != 0 {
+            return None;
+        }
+        uts.assume_init()
+    };
+
+    // SAFETY: the fields of `utsname` are nul terminated.
+    let release = unsafe { std::ffi::CStr::from_ptr(uts.release.as_ptr()) };
+    Some(release.to_string_lossy().into_owned())
+}
+
+#[cfg(not(unix))]
+fn kernel_release() -> Option<String> {
+    None
+}
+
+/// Collect the `Environment` of the calling crate
+///
+/// Fills in the name and version of the crate from cargo, and takes the
+/// names of the cargo features to report if they are enabled.
+///
+/// # Examples
+///
+/// ```rust
+/// let environment = color_eyre::environment!();
+/// let environment = color_eyre::environment!(features: ["tls", "gui"]);
+/// ```
+#[macro_export]
+macro_rules! environment {
+    () => {
+        $crate::environment!(features: [])
+    };
+    (features: [$($feature:literal),* $(,)?]) => {{
+        #[allow(unused_mut)]
+        let mut environment = $crate::environment::Environment::new(
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_VERSION"),
+        );
+        $(
+            if cfg!(feature = $feature) {
+                environment = environment.feature($feature);
+            }
+        )*
+        environment
+    }};
+}
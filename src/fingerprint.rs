@@ -0,0 +1,154 @@
+//! Stable fingerprints that identify the same bug across occurrences
+use crate::config::{filter_frames, split_hash, FilterCallback, Frame};
+use std::fmt;
+use std::panic::Location;
+
+/// The number of frames of the filtered backtrace that go into a fingerprint
+const FINGERPRINT_FRAMES: usize = 3;
+
+/// An identifier of the bug behind an error report or panic
+///
+/// # Details
+///
+/// The fingerprint is a hash of the messages of the error chain or the panic
+/// payload, the source file of the panic or `track_caller` location, and the
+/// names of the top frames of the filtered backtrace that are not dependency
+/// code. Numbers and quoted text in messages, line numbers and addresses are
+/// left out, so occurrences of the same bug with different data or in
+/// slightly changed code get the same fingerprint.
+///
+/// Fingerprints are only stable between builds of the same code with the same
+/// compiler, and the backtrace only contributes to them when it was captured.
+///
+/// # Examples
+///
+/// ```rust
+/// use color_eyre::{eyre::eyre, Handler};
+///
+/// color_eyre::install().unwrap();
+///
+/// let report = eyre!("failed to connect to port 8080");
+/// let handler = report.handler().downcast_ref::<Handler>().unwrap();
+/// let fingerprint = handler.fingerprint(&*report);
+///
+/// println!("known issue {}", fingerprint);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Fingerprint(u64);
+
+impl Fingerprint {
+    /// The fingerprint as an integer
+    pub fn as_u64(self) -> u64 {
+        self.0
+    }
+}
+
+impl fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+/// Computes fingerprints with the 64 bit FNV-1a hash, which unlike std's
+/// `DefaultHasher` is guaranteed not to change between Rust versions
+pub(crate) struct FingerprintBuilder(u64);
+
+impl FingerprintBuilder {
+    pub(crate) fn new() -> Self {
+        FingerprintBuilder(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, field: &str) {
+        // The separator keeps `("ab", "c")` and `("a", "bc")` apart.
+        for byte in field.bytes().chain(std::iter::once(0xff)) {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    /// Add an error message or panic payload
+    pub(crate) fn message(mut self, message: &str) -> Self {
+        self.write(&normalize(message));
+        self
+    }
+
+    /// Add the source file of a location
+    pub(crate) fn location(mut self, location: Option<&Location<'_>>) -> Self {
+        if let Some(location) = location {
+            self.write(&location.file().replace('\\', "/"));
+        }
+        self
+    }
+
+    /// Add the top frames of a backtrace, after applying the given filters
+    pub(crate) fn backtrace(
+        mut self,
+        backtrace: Option<&backtrace::Backtrace>,
+        filters: &[Box<FilterCallback>],
+    ) -> Self {
+        let backtrace = match backtrace {
+            Some(backtrace) => backtrace,
+            None => return self,
+        };
+
+        let frames = Frame::collect(backtrace);
+        let names = filter_frames(&frames, filters)
+            .into_iter()
+            .filter(|frame| !frame.is_dependency_code())
+            .filter_map(|frame| frame.name.as_deref())
+            .map(|name| split_hash(name).0)
+            .take(FINGERPRINT_FRAMES);
+
+        for name in names {
+            self.write(name);
+        }
+
+        self
+    }
+
+    pub(crate) fn finish(self) -> Fingerprint {
+        Fingerprint(self.0)
+    }
+}
+
+/// Mask the parts of a message that usually differ between occurrences of the
+/// same bug: words containing digits, such as numbers, ids and addresses, and
+/// quoted text, such as paths and user input
+fn normalize(message: &str) -> String {
+    let mut out = String::with_capacity(message.len());
+    let mut chars = message.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' | '`' => {
+                out.push(c);
+                out.push('…');
+                for next in chars.by_ref() {
+                    if next == c {
+                        out.push(c);
+                        break;
+                    }
+                }
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut word = c.to_string();
+                while let Some(&next) = chars.peek() {
+                    if !(next.is_alphanumeric() || next == '_') {
+                        break;
+                    }
+                    word.push(next);
+                    chars.next();
+                }
+
+                if word.chars().any(|c| c.is_ascii_digit()) {
+                    out.push('#');
+                } else {
+                    out.push_str(&word);
+                }
+            }
+            c => out.push(c),
+        }
+    }
+
+    out
+}
@@ -1,6 +1,17 @@
+#[cfg(feature = "issue-url")]
 use crate::{
-    config::BacktraceFormatter,
-    section::help::HelpInfo,
+    config::{filter_frames, Frame},
+    issue::{IssueAction, IssueContext, IssuePrompt},
+    section::issue::IssueSection,
+};
+use crate::{
+    config::{BacktraceFormatter, RedundantErrors, ReportPart},
+    fingerprint::{Fingerprint, FingerprintBuilder},
+    redact::Redacted,
+    section::{
+        help::{ErrorOrigin, FieldsSection, HelpInfo, LabelDisplay},
+        DefinedKind, SectionKind, SectionRef,
+    },
     writers::{EnvSection, WriterExt},
     Handler,
 };
@@ -29,6 +40,185 @@ impl Handler {
         self.span_trace.as_ref()
     }
 
+    /// Return the sections attached to the report, in the order they were
+    /// added
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use color_eyre::{eyre::eyre, section::SectionKind, Handler, Section};
+    ///
+    /// color_eyre::install().unwrap();
+    ///
+    /// let report = eyre!("failed to open the config").suggestion("run `app init` first");
+    /// let handler = report.handler().downcast_ref::<Handler>().unwrap();
+    ///
+    /// let suggestions: Vec<_> = handler
+    ///     .sections()
+    ///     .filter(|section| section.kind() == SectionKind::Suggestion)
+    ///     .map(|section| section.to_string())
+    ///     .collect();
+    ///
+    /// assert_eq!(suggestions, ["run `app init` first"]);
+    /// ```
+    pub fn sections(&self) -> impl Iterator<Item = SectionRef<'_>> {
+        self.sections.iter().map(SectionRef)
+    }
+
+    /// Return the error code set with `Section::code`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use color_eyre::{eyre::eyre, Handler, Section};
+    ///
+    /// color_eyre::install().unwrap();
+    ///
+    /// let report = eyre!("the config file is missing").code("E0042");
+    /// let handler = report.handler().downcast_ref::<Handler>().unwrap();
+    ///
+    /// let exit_code = match handler.code() {
+    ///     Some("E0042") => 78,
+    ///     _ => 1,
+    /// };
+    /// assert_eq!(exit_code, 78);
+    /// ```
+    pub fn code(&self) -> Option<&str> {
+        self.code.as_deref()
+    }
+
+    /// Return the documentation link of the error code, built from the
+    /// template set with `HookBuilder::error_docs_url`
+    pub fn docs_url(&self) -> Option<String> {
+        match (&self.code, &self.docs_url) {
+            (Some(code), Some(template)) => Some(template.replace("{code}", code)),
+            _ => None,
+        }
+    }
+
+    /// Return the errors attached with `Section::error`, in the order they
+    /// were added
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use color_eyre::{eyre::eyre, Handler, Section};
+    ///
+    /// color_eyre::install().unwrap();
+    ///
+    /// let report = eyre!("failed to import the file")
+    ///     .error(std::io::Error::from(std::io::ErrorKind::InvalidData));
+    /// let handler = report.handler().downcast_ref::<Handler>().unwrap();
+    ///
+    /// let kinds: Vec<_> = handler
+    ///     .errors()
+    ///     .filter_map(|error| error.downcast_ref::<std::io::Error>())
+    ///     .map(|error| error.kind())
+    ///     .collect();
+    ///
+    /// assert_eq!(kinds, [std::io::ErrorKind::InvalidData]);
+    /// ```
+    pub fn errors(&self) -> impl Iterator<Item = &(dyn std::error::Error + Send + Sync + 'static)> {
+        self.sections().filter_map(|section| section.error())
+    }
+
+    /// Remove the sections for which `keep` returns `false`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use color_eyre::{eyre::eyre, section::SectionKind, Handler, Section};
+    ///
+    /// color_eyre::install().unwrap();
+    ///
+    /// let mut report = eyre!("failed to open the config")
+    ///     .note("the config is read from the working directory")
+    ///     .suggestion("run `app init` first");
+    ///
+    /// // Show the suggestion elsewhere in the UI.
+    /// let handler = report.handler_mut().downcast_mut::<Handler>().unwrap();
+    /// handler.retain_sections(|section| section.kind() != SectionKind::Suggestion);
+    ///
+    /// assert!(!format!("{:?}", report).contains("Suggestion"));
+    /// ```
+    pub fn retain_sections<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(SectionRef<'_>) -> bool,
+    {
+        self.sections.retain(|section| keep(SectionRef(section)));
+    }
+
+    /// Replace the text of the section at `index`, keeping its kind
+    ///
+    /// The error of a `SectionKind::Error` section is replaced with an error
+    /// that displays `body` and has no source.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use color_eyre::{eyre::eyre, Handler, Section};
+    ///
+    /// color_eyre::install().unwrap();
+    ///
+    /// let mut report = eyre!("failed to connect").note("tried 127.0.0.1:8080");
+    ///
+    /// let handler = report.handler_mut().downcast_mut::<Handler>().unwrap();
+    /// handler.replace_section(0, "tried the local server");
+    ///
+    /// assert!(format!("{:?}", report).contains("tried the local server"));
+    /// ```
+    pub fn replace_section<D>(&mut self, index: usize, body: D)
+    where
+        D: std::fmt::Display + Send + Sync + 'static,
+    {
+        self.sections[index].replace(Box::new(body));
+    }
+
+    /// Return the fingerprint of the bug behind the report of `error`
+    ///
+    /// Computed from the messages of the error chain, the file of the
+    /// `track_caller` location and the top frames of the backtrace. See
+    /// `Fingerprint` for details.
+    pub fn fingerprint(&self, error: &(dyn std::error::Error + 'static)) -> Fingerprint {
+        #[cfg(feature = "capture-spantrace")]
+        let errors = eyre::Chain::new(error).filter(|e| e.span_trace().is_none());
+
+        #[cfg(not(feature = "capture-spantrace"))]
+        let errors = eyre::Chain::new(error);
+
+        let mut fingerprint = FingerprintBuilder::new();
+        for error in errors {
+            fingerprint = fingerprint.message(&error.to_string());
+        }
+
+        #[cfg(feature = "track-caller")]
+        let fingerprint = fingerprint.location(self.location);
+
+        fingerprint
+            .backtrace(self.backtrace.as_ref(), &self.filters)
+            .finish()
+    }
+
+    /// The origin of an error section attached at the caller's location
+    #[cfg_attr(feature = "track-caller", track_caller)]
+    pub(crate) fn error_origin(&self) -> ErrorOrigin {
+        let backtrace = if self.report_layout.has_error_backtraces() && self.backtrace.is_some() {
+            Some(Box::new(Backtrace::new()))
+        } else {
+            None
+        };
+
+        ErrorOrigin {
+            #[cfg(feature = "track-caller")]
+            location: std::panic::Location::caller(),
+            backtrace,
+        }
+    }
+
     pub(crate) fn format_backtrace<'a>(
         &'a self,
         trace: &'a backtrace::Backtrace,
@@ -37,6 +227,11 @@ impl Handler {
             filters: &self.filters,
             inner: trace,
             theme: self.theme,
+            #[cfg(feature = "capture-spantrace")]
+            span_trace: None,
+            #[cfg(feature = "capture-spantrace")]
+            span_filter: &self.span_filter,
+            layout: self.trace_layout,
         }
     }
 }
@@ -51,48 +246,71 @@ impl eyre::EyreHandler for Handler {
             return core::fmt::Debug::fmt(error, f);
         }
 
+        write!(
+            f,
+            "{}",
+            Redacted(&self.redactor, FormattedReport(self, error))
+        )
+    }
+
+    #[cfg(feature = "track-caller")]
+    fn track_caller(&mut self, location: &'static std::panic::Location<'static>) {
+        self.location = Some(location);
+    }
+}
+
+struct FormattedReport<'a>(&'a Handler, &'a (dyn std::error::Error + 'static));
+
+impl std::fmt::Display for FormattedReport<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Self(handler, error) = *self;
+        handler.fmt_report(error, f)
+    }
+}
+
+impl Handler {
+    fn fmt_report(
+        &self,
+        error: &(dyn std::error::Error + 'static),
+        f: &mut core::fmt::Formatter<'_>,
+    ) -> core::fmt::Result {
         #[cfg(feature = "capture-spantrace")]
-        let errors = || {
-            eyre::Chain::new(error)
-                .filter(|e| e.span_trace().is_none())
-                .enumerate()
-        };
+        let errors = eyre::Chain::new(error).filter(|e| e.span_trace().is_none());
 
         #[cfg(not(feature = "capture-spantrace"))]
-        let errors = || eyre::Chain::new(error).enumerate();
+        let errors = eyre::Chain::new(error);
 
-        for (n, error) in errors() {
+        if let Some(code) = &self.code {
             writeln!(f)?;
-            write!(indented(f).ind(n), "{}", self.theme.error.style(error))?;
+            write!(f, "   Code: {}", self.theme.error.style(code))?;
+            if let Some(docs_url) = self.docs_url() {
+                write!(f, " ({})", docs_url)?;
+            }
         }
 
-        let mut separated = f.header("\n\n");
-
-        #[cfg(feature = "track-caller")]
-        write!(
-            separated.ready(),
-            "{}",
-            crate::SectionExt::header(
-                crate::fmt::LocationSection(self.location, self.theme),
-                "Location:"
-            )
-        )?;
+        let mut parent: Option<String> = None;
+        let mut n = 0;
+        for error in errors {
+            let message = error.to_string();
+            let redundant = parent
+                .as_ref()
+                .is_some_and(|parent| !message.is_empty() && parent.contains(&message));
+            let style = match self.report_layout.redundant_errors_mode() {
+                RedundantErrors::Elide if redundant => {
+                    parent = Some(message);
+                    continue;
+                }
+                RedundantErrors::Dim if redundant => self.theme.redundant_error,
+                _ => self.theme.error,
+            };
 
-        for section in self
-            .sections
-            .iter()
-            .filter(|s| matches!(s, HelpInfo::Error(_, _)))
-        {
-            write!(separated.ready(), "{}", section)?;
+            writeln!(f)?;
+            write!(indented(f).ind(n), "{}", style.style(&message))?;
+            n += 1;
+            parent = Some(message);
         }
 
-        for section in self
-            .sections
-            .iter()
-            .filter(|s| matches!(s, HelpInfo::Custom(_)))
-        {
-            write!(separated.ready(), "{}", section)?;
-        }
+        let mut separated = f.header("\n\n");
 
         #[cfg(feature = "capture-spantrace")]
         let span_trace = self
@@ -100,38 +318,81 @@ impl eyre::EyreHandler for Handler {
             .as_ref()
             .or_else(|| get_deepest_spantrace(error));
 
-        #[cfg(feature = "capture-spantrace")]
-        {
-            if let Some(span_trace) = span_trace {
-                write!(
-                    &mut separated.ready(),
-                    "{}",
-                    crate::writers::FormattedSpanTrace(span_trace)
-                )?;
+        let fmted_bt = self.backtrace.as_ref().map(|backtrace| {
+            let fmted_bt = self.format_backtrace(backtrace);
+
+            #[cfg(feature = "capture-spantrace")]
+            let fmted_bt = fmted_bt.with_span_trace(span_trace);
+
+            fmted_bt
+        });
+
+        let mut defined: Vec<DefinedKind> = Vec::new();
+        for section in &self.sections {
+            if let HelpInfo::Defined(kind, ..) = section {
+                if !defined.iter().any(|k| k.label == kind.label) {
+                    defined.push(*kind);
+                }
             }
         }
 
-        if let Some(backtrace) = self.backtrace.as_ref() {
-            let fmted_bt = self.format_backtrace(&backtrace);
+        for group in self.report_layout.groups(&defined) {
+            let mut kinds = Vec::new();
 
-            write!(
-                indented(&mut separated.ready()).with_format(Format::Uniform { indentation: "  " }),
-                "{}",
-                fmted_bt
-            )?;
-        }
+            for part in group {
+                match part {
+                    #[cfg(feature = "track-caller")]
+                    ReportPart::Location => write!(
+                        separated.ready(),
+                        "{}",
+                        crate::SectionExt::header(
+                            crate::fmt::LocationSection(self.location, self.theme),
+                            "Location:"
+                        )
+                    )?,
+                    #[cfg(feature = "capture-spantrace")]
+                    ReportPart::SpanTrace => {
+                        if let Some(span_trace) =
+                            span_trace.filter(|_| !fmted_bt.iter().any(|bt| bt.is_merged()))
+                        {
+                            write!(
+                                &mut separated.ready(),
+                                "{}",
+                                crate::writers::FormattedSpanTrace(
+                                    span_trace,
+                                    &self.span_filter,
+                                    self.theme
+                                )
+                            )?;
+                        }
+                    }
+                    ReportPart::Backtrace => {
+                        if let Some(fmted_bt) = &fmted_bt {
+                            write!(
+                                indented(&mut separated.ready())
+                                    .with_format(Format::Uniform { indentation: "  " }),
+                                "{}",
+                                fmted_bt
+                            )?;
+                        }
+                    }
+                    ReportPart::Section(kind) => kinds.push(kind),
+                    #[allow(unreachable_patterns)]
+                    _ => {}
+                }
+            }
 
-        let f = separated.ready();
-        let mut h = f.header("\n");
-        let mut f = h.in_progress();
+            if !kinds.is_empty() {
+                self.fmt_sections(&kinds, &mut separated)?;
+            }
+        }
 
-        for section in self
-            .sections
-            .iter()
-            .filter(|s| !matches!(s, HelpInfo::Custom(_) | HelpInfo::Error(_, _)))
-        {
-            write!(&mut f, "{}", section)?;
-            f = h.ready();
+        if self.display_fingerprint {
+            write!(
+                &mut separated.ready(),
+                "Fingerprint: {}",
+                self.fingerprint(error)
+            )?;
         }
 
         if self.display_env_section {
@@ -145,33 +406,428 @@ impl eyre::EyreHandler for Handler {
         }
 
         #[cfg(feature = "issue-url")]
-        if self.issue_url.is_some() && (*self.issue_filter)(crate::ErrorKind::Recoverable(error)) {
-            let url = self.issue_url.as_ref().unwrap();
-            let mut payload = String::from("Error: ");
-            for (n, error) in errors() {
-                writeln!(&mut payload)?;
-                write!(indented(&mut payload).ind(n), "{}", error)?;
+        if self.issue_url.is_some() {
+            let payload = issue_payload(error)?;
+            let message = error.to_string();
+
+            if let Some(issue_section) = self.issue_section(error, &payload, &message) {
+                write!(&mut separated.ready(), "{}", issue_section)?;
             }
+        }
 
-            let issue_section = crate::section::github::IssueSection::new(url, &payload)
-                .with_backtrace(self.backtrace.as_ref())
-                .with_metadata(&**self.issue_metadata);
+        Ok(())
+    }
 
-            #[cfg(feature = "capture-spantrace")]
-            let issue_section = issue_section.with_span_trace(span_trace);
+    /// Print the sections of the given kinds, in the order they were attached
+    fn fmt_sections<W: Write>(
+        &self,
+        kinds: &[SectionKind],
+        separated: &mut crate::writers::HeaderWriter<'_, str, W>,
+    ) -> core::fmt::Result {
+        let mut counted: Vec<Counted<'_>> = Vec::new();
+        for section in self
+            .sections
+            .iter()
+            .map(SectionRef)
+            .filter(|s| kinds.contains(&s.kind()))
+        {
+            if self.report_layout.is_deduplicated() && section.kind() != SectionKind::Field {
+                let text = section.0.to_string();
+                let duplicate = counted
+                    .iter_mut()
+                    .find(|c| c.0.kind() == section.kind() && c.0 .0.to_string() == text);
+
+                if let Some(duplicate) = duplicate {
+                    duplicate.1 += 1;
+                    continue;
+                }
+            }
+
+            counted.push(Counted(section, 1));
+        }
+        let sections = || counted.iter().copied();
+
+        let errors: Vec<_> = sections()
+            .filter(|s| s.0.kind() == SectionKind::Error)
+            .collect();
+
+        let (errors, hidden) = match self.error_limit {
+            Some(limit) if limit < errors.len() => {
+                let hidden = errors[limit..].iter().map(|counted| counted.1).sum();
+                (&errors[..limit], hidden)
+            }
+            _ => (&errors[..], 0),
+        };
+
+        if !self.report_layout.is_error_tree() {
+            for section in errors {
+                write!(separated.ready(), "{}", section)?;
+            }
+
+            if hidden > 0 {
+                write!(separated.ready(), "... and {} more", hidden)?;
+            }
+        } else if !errors.is_empty() || hidden > 0 {
+            write!(separated.ready(), "{}", ErrorTree(self, errors, hidden))?;
+        }
+
+        let fields: Vec<_> = sections().filter_map(|s| s.0.field()).collect();
+
+        if !fields.is_empty() {
+            write!(
+                separated.ready(),
+                "{}",
+                crate::SectionExt::header(FieldsSection(fields).to_string(), "Context:")
+            )?;
+        }
 
-            write!(&mut separated.ready(), "{}", issue_section)?;
+        for section in sections().filter(|s| s.0.kind() == SectionKind::Custom) {
+            write!(separated.ready(), "{}", section)?;
+        }
+
+        let is_help = |kind: &SectionKind| {
+            matches!(
+                kind,
+                SectionKind::Note
+                    | SectionKind::Warning
+                    | SectionKind::Suggestion
+                    | SectionKind::Defined(_)
+            )
+        };
+
+        if self.report_layout.is_grouped() {
+            for &kind in kinds.iter().filter(|kind| is_help(kind)) {
+                let group: Vec<_> = sections().filter(|s| s.0.kind() == kind).collect();
+
+                if !group.is_empty() {
+                    write!(separated.ready(), "{}", SectionGroup(&group, self.theme))?;
+                }
+            }
+        } else if sections().any(|s| is_help(&s.0.kind())) {
+            let f = separated.ready();
+            let mut h = f.header("\n");
+            let mut f = h.in_progress();
+
+            for section in sections().filter(|s| is_help(&s.0.kind())) {
+                write!(&mut f, "{}", section)?;
+                f = h.ready();
+            }
         }
 
         Ok(())
     }
+}
 
-    #[cfg(feature = "track-caller")]
-    fn track_caller(&mut self, location: &'static std::panic::Location<'static>) {
-        self.location = Some(location);
+/// A section with the number of times it was attached, see
+/// `ReportLayout::deduplicate_sections`
+#[derive(Clone, Copy)]
+struct Counted<'a>(SectionRef<'a>, usize);
+
+impl Counted<'_> {
+    fn fmt_count(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.1 > 1 {
+            write!(f, " (×{})", self.1)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Counted<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0 .0)?;
+        self.fmt_count(f)
+    }
+}
+
+/// The attached errors printed as a numbered tree, see
+/// `ReportLayout::error_tree`
+struct ErrorTree<'a>(&'a Handler, &'a [Counted<'a>], usize);
+
+impl std::fmt::Display for ErrorTree<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ErrorTree(handler, errors, hidden) = *self;
+
+        write!(f, "Errors:")?;
+
+        for (n, counted) in errors.iter().enumerate() {
+            let (error, theme, origin) = match counted.0 .0 {
+                HelpInfo::Error(error, theme, origin) => (error, theme, origin),
+                _ => continue,
+            };
+            let number = format!(
+                "{:>width$}: ",
+                n + 1,
+                width = errors.len().to_string().len()
+            );
+            let (branch, stem) = if n + 1 == errors.len() && hidden == 0 {
+                ("└─ ", "   ")
+            } else {
+                ("├─ ", "│  ")
+            };
+            let indent = format!("{}{:width$}", stem, "", width = number.chars().count());
+
+            // Every line after the first is continued below the number.
+            let lines = |text: &str, f: &mut std::fmt::Formatter<'_>| {
+                for line in text.lines() {
+                    write!(f, "\n{}", indent)?;
+                    write!(f, "{}", line)?;
+                }
+                Ok::<_, std::fmt::Error>(())
+            };
+
+            let message = error.to_string();
+            let mut message_lines = message.lines();
+            write!(
+                f,
+                "\n{}{}{}",
+                branch,
+                number,
+                theme
+                    .help_info_error
+                    .style(message_lines.next().unwrap_or_default())
+            )?;
+            for line in message_lines {
+                write!(f, "\n{}{}", indent, theme.help_info_error.style(line))?;
+            }
+            counted.fmt_count(f)?;
+
+            for source in std::iter::successors(error.source(), |e| e.source()) {
+                lines(&format!("caused by: {}", source), f)?;
+            }
+
+            #[cfg(feature = "track-caller")]
+            lines(
+                &format!(
+                    "at {}",
+                    crate::fmt::LocationSection(Some(origin.location), *theme)
+                ),
+                f,
+            )?;
+
+            if let Some(backtrace) = &origin.backtrace {
+                write!(f, "\n{}", stem.trim_end())?;
+                let backtrace = handler.format_backtrace(backtrace).to_string();
+                for line in backtrace.lines() {
+                    write!(f, "\n{}", format!("{}{}", indent, line).trim_end())?;
+                }
+            }
+        }
+
+        if hidden > 0 {
+            write!(f, "\n└─ ... and {} more", hidden)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Sections of one kind printed as a list under a single header
+struct SectionGroup<'a>(&'a [Counted<'a>], crate::config::Theme);
+
+impl std::fmt::Display for SectionGroup<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use owo_colors::OwoColorize;
+
+        let SectionGroup(sections, theme) = self;
+        match sections[0].0 .0 {
+            HelpInfo::Note(..) => write!(f, "{}", "Notes:".style(theme.help_info_note))?,
+            HelpInfo::Warning(..) => write!(f, "{}", "Warnings:".style(theme.help_info_warning))?,
+            HelpInfo::Defined(kind, ..) => write!(f, "{}:", LabelDisplay(kind, theme))?,
+            _ => write!(f, "{}", "Suggestions:".style(theme.help_info_suggestion))?,
+        }
+
+        for section in sections.iter() {
+            for (n, line) in section.0.to_string().lines().enumerate() {
+                let bullet = if n == 0 { "-" } else { " " };
+                write!(f, "\n   {} {}", bullet, line)?;
+            }
+            section.fmt_count(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "issue-url")]
+impl Handler {
+    /// The issue section for the report of `error`, or `None` if the issue
+    /// filter skips it
+    fn issue_section<'a>(
+        &'a self,
+        error: &'a (dyn std::error::Error + 'static),
+        payload: &'a str,
+        message: &'a str,
+    ) -> Option<IssueSection<'a>> {
+        let fingerprint = self.fingerprint(error);
+        let frames = self
+            .backtrace
+            .as_ref()
+            .map(Frame::collect)
+            .unwrap_or_default();
+        let mut decision = (*self.issue_filter)(&IssueContext {
+            kind: crate::ErrorKind::Recoverable(error),
+            fingerprint,
+            #[cfg(feature = "track-caller")]
+            location: self.location,
+            #[cfg(not(feature = "track-caller"))]
+            location: None,
+            frames: filter_frames(&frames, &self.filters),
+            sections: &self.sections,
+        });
+
+        if decision.action == IssueAction::Skip {
+            return None;
+        }
+
+        if let Some(code) = &self.code {
+            let mut code = vec![("code".to_string(), code.clone())];
+            code.extend(self.docs_url().map(|url| ("docs".to_string(), url)));
+            decision.metadata.splice(0..0, code);
+        }
+
+        let url = self.issue_url.as_deref().unwrap_or_default();
+        let issue_section = IssueSection::new(url, payload)
+            .with_fingerprint(fingerprint)
+            .with_decision(decision)
+            .with_tracker(&*self.issue_tracker)
+            .with_max_len(self.issue_url_max_length)
+            .with_template(&self.issue_template)
+            .with_message(message)
+            .with_backtrace(self.backtrace.as_ref())
+            .with_metadata(&self.issue_metadata)
+            .with_sections(&self.sections)
+            .with_redactor(&self.redactor);
+
+        #[cfg(feature = "track-caller")]
+        let issue_section = issue_section.with_location(self.location);
+
+        #[cfg(feature = "capture-spantrace")]
+        let issue_section = issue_section
+            .with_span_trace(
+                self.span_trace
+                    .as_ref()
+                    .or_else(|| get_deepest_spantrace(error)),
+            )
+            .with_span_filter(&self.span_filter);
+
+        Some(issue_section)
+    }
+
+    /// Ask whether to open the issue for the report of `error` with `ask`, see
+    /// `color_eyre::issue::prompt`
+    pub(crate) fn prompt_issue(
+        &self,
+        error: &(dyn std::error::Error + 'static),
+        ask: impl FnOnce(&IssueSection<'_>, &IssuePrompt) -> std::io::Result<()>,
+    ) -> std::io::Result<()> {
+        if self.issue_url.is_none() {
+            return Ok(());
+        }
+
+        let default_prompt = IssuePrompt::new();
+        let prompt = self.issue_prompt.as_deref().unwrap_or(&default_prompt);
+        let payload = issue_payload(error).map_err(|_| std::io::ErrorKind::Other)?;
+        let message = error.to_string();
+
+        match self.issue_section(error, &payload, &message) {
+            Some(issue_section) => ask(&issue_section, prompt),
+            None => Ok(()),
+        }
+    }
+
+    /// Save a bug report bundle for the report of `error`, see
+    /// `color_eyre::issue::save_bundle`
+    pub(crate) fn save_bundle(
+        &self,
+        error: &(dyn std::error::Error + 'static),
+    ) -> std::io::Result<std::path::PathBuf> {
+        let bundle = self
+            .issue_bundle
+            .as_deref()
+            .ok_or_else(|| std::io::Error::other("no issue bundle is configured"))?;
+        let payload = issue_payload(error).map_err(|_| std::io::ErrorKind::Other)?;
+        let message = error.to_string();
+
+        match self.issue_section(error, &payload, &message) {
+            Some(issue_section) => crate::section::issue::save_bundle(issue_section, bundle),
+            None => Err(std::io::Error::other(
+                "the issue filter skipped this report",
+            )),
+        }
+    }
+}
+
+#[cfg(feature = "crash-report")]
+impl Handler {
+    /// Send a crash report for the report of `error`, see
+    /// `color_eyre::crash_report::send`
+    pub(crate) fn send_crash_report(
+        &self,
+        error: &(dyn std::error::Error + 'static),
+    ) -> std::io::Result<Option<crate::crash_report::Delivery>> {
+        let crash_reporter = match &self.crash_reporter {
+            Some(crash_reporter) => crash_reporter,
+            None => return Ok(None),
+        };
+
+        #[cfg(feature = "capture-spantrace")]
+        let mut errors = eyre::Chain::new(error).filter(|e| e.span_trace().is_none());
+
+        #[cfg(not(feature = "capture-spantrace"))]
+        let mut errors = eyre::Chain::new(error);
+
+        let message = errors.next().map(ToString::to_string).unwrap_or_default();
+        let mut report = crate::crash_report::CrashReport::new(
+            "error",
+            message,
+            self.fingerprint(error),
+            &self.filters,
+            &self.redactor,
+        );
+        report.causes = errors.map(|error| error.to_string()).collect();
+        report.fields = self.sections().filter_map(|s| s.field()).collect();
+        report.code = self.code.as_deref();
+        report.docs_url = self.docs_url();
+        report.backtrace = self.backtrace.as_ref();
+
+        #[cfg(feature = "capture-spantrace")]
+        report.set_span_trace(
+            self.span_trace
+                .as_ref()
+                .or_else(|| get_deepest_spantrace(error)),
+            &self.span_filter,
+        );
+
+        #[cfg(feature = "track-caller")]
+        {
+            report.location = self.location;
+        }
+
+        crash_reporter.deliver(&report).map(Some)
     }
 }
 
+/// The error chain of a report, as written to issues
+#[cfg(feature = "issue-url")]
+fn issue_payload(error: &(dyn std::error::Error + 'static)) -> Result<String, std::fmt::Error> {
+    #[cfg(feature = "capture-spantrace")]
+    let errors = eyre::Chain::new(error)
+        .filter(|e| e.span_trace().is_none())
+        .enumerate();
+
+    #[cfg(not(feature = "capture-spantrace"))]
+    let errors = eyre::Chain::new(error).enumerate();
+
+    let mut payload = String::from("Error: ");
+    for (n, error) in errors {
+        writeln!(&mut payload)?;
+        write!(indented(&mut payload).ind(n), "{}", error)?;
+    }
+
+    Ok(payload)
+}
+
 #[cfg(feature = "capture-spantrace")]
 pub(crate) fn get_deepest_spantrace<'a>(
     error: &'a (dyn std::error::Error + 'static),
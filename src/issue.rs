@@ -0,0 +1,896 @@
+//! Issue trackers that color-eyre can generate new issue urls for
+//!
+//! The tracker is selected with `HookBuilder::issue_tracker` and defaults to
+//! `GitHub`. For users without access to the tracker, an `IssueBundle` saves
+//! the same information to a local file instead. In terminals, an
+//! `IssuePrompt` previews the issue and asks whether to open it.
+use crate::{config::Frame, section::help::HelpInfo, section::SectionRef};
+use std::fmt;
+use std::io;
+use std::panic::Location;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use url::Url;
+
+/// An issue tracker backend used to build the urls of pre-filled new issues
+///
+/// # Examples
+///
+/// ```rust
+/// use color_eyre::issue::{IssueTracker, Markup, NewIssue};
+///
+/// /// A tracker with a form that takes the issue as `subject` and `text`
+/// struct Internal;
+///
+/// impl IssueTracker for Internal {
+///     fn new_issue_url(&self, url: &str, issue: &NewIssue<'_>) -> Option<String> {
+///         let mut url = url::Url::parse(url).ok()?;
+///         url.query_pairs_mut()
+///             .append_pair("subject", issue.title)
+///             .append_pair("text", issue.body);
+///         Some(url.into())
+///     }
+///
+///     fn markup(&self) -> Markup {
+///         Markup::Markdown
+///     }
+/// }
+///
+/// color_eyre::config::HookBuilder::default()
+///     .issue_url("https://bugs.example.com/new")
+///     .issue_tracker(Internal)
+///     .install()
+///     .unwrap();
+/// ```
+pub trait IssueTracker: Send + Sync + 'static {
+    /// Build the url that opens a pre-filled form for the given issue, or
+    /// `None` if `url` is invalid
+    ///
+    /// `url` is the url configured with `HookBuilder::issue_url` and the body
+    /// of the issue is written in the tracker's `markup`.
+    fn new_issue_url(&self, url: &str, issue: &NewIssue<'_>) -> Option<String>;
+
+    /// Build the url that searches for existing issues containing `query`,
+    /// or `None` if searching isn't supported
+    ///
+    /// `url` is the url configured with `HookBuilder::issue_url`. Used when
+    /// the issue filter returns `IssueAction::Search`, with the fingerprint of
+    /// the report as the query.
+    fn search_url(&self, url: &str, query: &str) -> Option<String> {
+        let _ = (url, query);
+        None
+    }
+
+    /// The markup language issue bodies are written in
+    fn markup(&self) -> Markup {
+        Markup::Markdown
+    }
+}
+
+/// What to link to for a report, as decided by `HookBuilder::issue_filter`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum IssueAction {
+    /// Don't link to the issue tracker
+    Skip,
+    /// Link to a pre-filled new issue
+    Create,
+    /// Link to a search for existing issues with the same `Fingerprint`,
+    /// falling back to a new issue if the tracker doesn't support searching
+    Search,
+}
+
+impl From<bool> for IssueAction {
+    fn from(create: bool) -> Self {
+        if create {
+            IssueAction::Create
+        } else {
+            IssueAction::Skip
+        }
+    }
+}
+
+/// What `HookBuilder::issue_filter` decided for a report, along with labels
+/// and metadata to add to its issue
+///
+/// Filters can also return an `IssueAction` or a `bool`, which convert to a
+/// decision without extra labels or metadata.
+///
+/// # Examples
+///
+/// ```rust
+/// use color_eyre::issue::{IssueAction, IssueDecision};
+///
+/// let decision = IssueDecision::new(IssueAction::Create)
+///     .label("crash")
+///     .metadata("plugin", "markdown-preview");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IssueDecision {
+    pub(crate) action: IssueAction,
+    pub(crate) labels: Vec<String>,
+    pub(crate) metadata: Vec<(String, String)>,
+}
+
+impl IssueDecision {
+    /// Take the given action, without extra labels or metadata
+    pub fn new(action: IssueAction) -> Self {
+        IssueDecision {
+            action,
+            labels: Vec::new(),
+            metadata: Vec::new(),
+        }
+    }
+
+    /// Add a label to the issue, in addition to those of the `IssueTemplate`
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.labels.push(label.into());
+        self
+    }
+
+    /// Add an entry to the metadata table of the issue, after the entries
+    /// added with `HookBuilder::add_issue_metadata`
+    pub fn metadata(mut self, key: impl Into<String>, value: impl ToString) -> Self {
+        self.metadata.push((key.into(), value.to_string()));
+        self
+    }
+
+    /// The action to take
+    pub fn action(&self) -> IssueAction {
+        self.action
+    }
+}
+
+impl From<IssueAction> for IssueDecision {
+    fn from(action: IssueAction) -> Self {
+        IssueDecision::new(action)
+    }
+}
+
+impl From<bool> for IssueDecision {
+    fn from(create: bool) -> Self {
+        IssueDecision::new(create.into())
+    }
+}
+
+/// What `HookBuilder::issue_filter` knows about a report
+///
+/// # Examples
+///
+/// Only link to issues for bugs in this crate, and label panics in plugins:
+///
+/// ```rust
+/// use color_eyre::issue::{IssueAction, IssueDecision};
+///
+/// color_eyre::config::HookBuilder::default()
+///     .issue_url(concat!(env!("CARGO_PKG_REPOSITORY"), "/issues/new"))
+///     .issue_filter(|context| {
+///         if !context.has_frame_from(env!("CARGO_PKG_NAME")) {
+///             return IssueDecision::new(IssueAction::Skip);
+///         }
+///
+///         match context.location() {
+///             Some(location) if location.file().starts_with("src/plugins/") => {
+///                 IssueDecision::new(IssueAction::Create)
+///                     .label("plugins")
+///                     .metadata("plugin file", location.file())
+///             }
+///             _ => IssueDecision::new(IssueAction::Create),
+///         }
+///     })
+///     .install()
+///     .unwrap();
+/// ```
+pub struct IssueContext<'a> {
+    pub(crate) kind: crate::ErrorKind<'a>,
+    pub(crate) fingerprint: crate::Fingerprint,
+    pub(crate) location: Option<&'a Location<'a>>,
+    pub(crate) frames: Vec<&'a Frame>,
+    pub(crate) sections: &'a [HelpInfo],
+}
+
+impl<'a> IssueContext<'a> {
+    /// The panic payload or error being reported
+    pub fn kind(&self) -> &crate::ErrorKind<'a> {
+        &self.kind
+    }
+
+    /// The `Fingerprint` of the report
+    pub fn fingerprint(&self) -> crate::Fingerprint {
+        self.fingerprint
+    }
+
+    /// The location of the panic, or the `track_caller` location of the
+    /// error
+    pub fn location(&self) -> Option<&'a Location<'a>> {
+        self.location
+    }
+
+    /// The frames of the backtrace after applying the frame filters, or none
+    /// if no backtrace was captured
+    pub fn frames(&self) -> &[&'a Frame] {
+        &self.frames
+    }
+
+    /// Whether any of the filtered frames is a function of the given crate
+    ///
+    /// Dashes in the name are treated as underscores, so
+    /// `env!("CARGO_PKG_NAME")` can be passed to check for the calling crate.
+    /// Always `false` if no backtrace was captured.
+    pub fn has_frame_from(&self, krate: &str) -> bool {
+        let krate = krate.replace('-', "_");
+
+        self.frames
+            .iter()
+            .filter_map(|frame| frame.name.as_deref())
+            .any(|name| {
+                name.trim_start_matches('<')
+                    .strip_prefix(krate.as_str())
+                    .is_some_and(|rest| rest.starts_with("::") || rest.starts_with('['))
+            })
+    }
+
+    /// The sections attached to the report, in the order they were added
+    ///
+    /// Panics have no sections.
+    pub fn sections(&self) -> impl Iterator<Item = SectionRef<'a>> + 'a {
+        self.sections.iter().map(SectionRef)
+    }
+}
+
+impl fmt::Debug for IssueContext<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IssueContext")
+            .field("fingerprint", &self.fingerprint)
+            .field("location", &self.location)
+            .field("frames", &self.frames)
+            .field("sections", &self.sections)
+            .finish()
+    }
+}
+
+/// The contents of a generated issue
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct NewIssue<'a> {
+    /// The title of the issue
+    pub title: &'a str,
+    /// The body of the issue
+    pub body: &'a str,
+    /// The labels to add to the issue
+    pub labels: &'a [String],
+    /// The users to assign to the issue
+    pub assignees: &'a [String],
+    /// The name of the issue template or form to use
+    pub template: Option<&'a str>,
+}
+
+/// The template for the title and body of generated issues
+///
+/// # Details
+///
+/// The title and body can contain the following placeholders:
+///
+/// - `{message}`: the first line of the error message or panic payload
+/// - `{location}`: the location of the panic or error, if known
+/// - `{version}`: the value of the `version` entry added with
+///   `HookBuilder::add_issue_metadata`
+/// - `{fingerprint}`: the `Fingerprint` of the report
+///
+/// And in the body only:
+///
+/// - `{error}`: the full error report message
+/// - `{metadata}`: the metadata table
+/// - `{sections}`: the notes, warnings, suggestions and custom sections
+///   attached to the report
+/// - `{span_trace}`: the span trace
+/// - `{backtrace}`: the backtrace
+///
+/// Placeholders without a value are replaced with nothing, unknown
+/// placeholders are kept as they are, and `{{` and `}}` are replaced with `{`
+/// and `}`. Without a body template, the issue body
+/// lists the error, metadata, span trace and backtrace under their own
+/// headings.
+///
+/// Labels, assignees and the template name are passed to the issue tracker,
+/// which ignores the ones it doesn't support.
+///
+/// # Examples
+///
+/// ```rust
+/// use color_eyre::issue::IssueTemplate;
+///
+/// color_eyre::config::HookBuilder::default()
+///     .issue_url(concat!(env!("CARGO_PKG_REPOSITORY"), "/issues/new"))
+///     .add_issue_metadata("version", env!("CARGO_PKG_VERSION"))
+///     .issue_template(
+///         IssueTemplate::new()
+///             .title("crash: {message}")
+///             .body("Happened in {version}\n\n{error}\n\n{backtrace}")
+///             .label("bug")
+///             .label("triage")
+///             .assignee("octocat")
+///             .template("bug_report.yml"),
+///     )
+///     .install()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct IssueTemplate {
+    pub(crate) title: String,
+    pub(crate) body: Option<String>,
+    pub(crate) labels: Vec<String>,
+    pub(crate) assignees: Vec<String>,
+    pub(crate) template: Option<String>,
+}
+
+impl IssueTemplate {
+    /// Create the default template, titled `<autogenerated-issue>`
+    pub fn new() -> Self {
+        IssueTemplate {
+            title: "<autogenerated-issue>".to_string(),
+            body: None,
+            labels: Vec::new(),
+            assignees: Vec::new(),
+            template: None,
+        }
+    }
+
+    /// Set the template for the issue title
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Set the template for the issue body
+    pub fn body(mut self, body: impl Into<String>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    /// Add a label to the issue
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.labels.push(label.into());
+        self
+    }
+
+    /// Assign a user to the issue
+    pub fn assignee(mut self, assignee: impl Into<String>) -> Self {
+        self.assignees.push(assignee.into());
+        self
+    }
+
+    /// Use the issue template or form with the given name, e.g. the file name
+    /// of a GitHub issue form
+    pub fn template(mut self, template: impl Into<String>) -> Self {
+        self.template = Some(template.into());
+        self
+    }
+}
+
+impl Default for IssueTemplate {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Where and how to save offline bug report bundles
+///
+/// # Details
+///
+/// A bundle is a self-contained markdown file with the error or panic
+/// message, the issue metadata, information about the environment, the span
+/// trace and backtrace, and optionally the end of a log file. Each bundle is
+/// written to a new file in the bundle directory.
+///
+/// The panic hook saves a bundle for every panic that passes the
+/// `issue_filter`, and prints the path of the file and instructions on what
+/// to do with it. Bundles for `Report`s are saved with [`save_bundle`].
+///
+/// Bundles are redacted with the same rules as reports, and are never
+/// shortened.
+///
+/// # Examples
+///
+/// ```rust
+/// use color_eyre::issue::IssueBundle;
+///
+/// color_eyre::config::HookBuilder::default()
+///     .add_issue_metadata("version", env!("CARGO_PKG_VERSION"))
+///     .issue_bundle(
+///         IssueBundle::new(std::env::temp_dir().join("my-app-reports"))
+///             .log_tail("/var/log/my-app.log", 50)
+///             .instructions("Please send this file to support@example.com"),
+///     )
+///     .install()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct IssueBundle {
+    pub(crate) dir: PathBuf,
+    pub(crate) log: Option<(PathBuf, usize)>,
+    pub(crate) instructions: String,
+}
+
+impl IssueBundle {
+    /// Save bundles to the given directory, which is created if it doesn't
+    /// exist
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        IssueBundle {
+            dir: dir.into(),
+            log: None,
+            instructions: "Please include this file when reporting this error.".to_string(),
+        }
+    }
+
+    /// Include the last `lines` lines of the log file at `path`
+    pub fn log_tail(mut self, path: impl Into<PathBuf>, lines: usize) -> Self {
+        self.log = Some((path.into(), lines));
+        self
+    }
+
+    /// Set the instructions printed after the path of a saved bundle
+    pub fn instructions(mut self, instructions: impl Into<String>) -> Self {
+        self.instructions = instructions.into();
+        self
+    }
+}
+
+/// A callback that opens a url in the browser
+pub type OpenerCallback = dyn Fn(&str) -> io::Result<()> + Send + Sync + 'static;
+
+/// Asks whether to open the issue for a report in the browser
+///
+/// # Details
+///
+/// When stdin and stderr are terminals, the prompt shows the title and body
+/// of the issue that would be submitted, with redactions applied, and asks
+/// whether to open it in the browser, save its url to a file in the save
+/// directory, or do nothing. Otherwise it does nothing, so scripts and CI
+/// only see the url printed in the report.
+///
+/// Panics are prompted for by the panic hook when it is configured with
+/// `HookBuilder::issue_prompt`. Reports are prompted for with [`prompt`],
+/// usually just before exiting from `main`.
+///
+/// # Examples
+///
+/// ```rust
+/// use color_eyre::issue::IssuePrompt;
+///
+/// color_eyre::config::HookBuilder::default()
+///     .issue_url("https://github.com/yaahc/jane-eyre/issues/new")
+///     .issue_prompt(IssuePrompt::new().save_dir(std::env::temp_dir().join("my-app")))
+///     .install()
+///     .unwrap();
+/// ```
+pub struct IssuePrompt {
+    pub(crate) save_dir: PathBuf,
+    pub(crate) opener: Box<OpenerCallback>,
+}
+
+impl IssuePrompt {
+    /// Open urls with the platform's default browser, and save them to the
+    /// temporary directory
+    pub fn new() -> Self {
+        IssuePrompt {
+            save_dir: std::env::temp_dir(),
+            opener: Box::new(open_url),
+        }
+    }
+
+    /// Save urls to the given directory, which is created if it doesn't
+    /// exist
+    pub fn save_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.save_dir = dir.into();
+        self
+    }
+
+    /// Open urls with the given callback instead of the default browser
+    ///
+    /// If the callback fails, the url is saved to a file instead.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use color_eyre::issue::IssuePrompt;
+    ///
+    /// let prompt = IssuePrompt::new().opener(Box::new(|url| {
+    ///     std::process::Command::new("firefox").arg(url).status()?;
+    ///     Ok(())
+    /// }));
+    /// ```
+    pub fn opener(mut self, opener: Box<OpenerCallback>) -> Self {
+        self.opener = opener;
+        self
+    }
+}
+
+impl Default for IssuePrompt {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for IssuePrompt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IssuePrompt")
+            .field("save_dir", &self.save_dir)
+            .finish()
+    }
+}
+
+/// Ask whether to open the issue for `report` in the browser
+///
+/// Uses the prompt configured with `HookBuilder::issue_prompt`, or a default
+/// `IssuePrompt`. Does nothing if no `issue_url` is set, the `issue_filter`
+/// skips the report, stdin or stderr is not a terminal, or the report was not
+/// created with color-eyre's handler.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use color_eyre::eyre::{eyre, Report};
+///
+/// fn run() -> Result<(), Report> {
+///     Err(eyre!("something went wrong"))
+/// }
+///
+/// fn main() {
+///     color_eyre::config::HookBuilder::default()
+///         .issue_url("https://github.com/yaahc/jane-eyre/issues/new")
+///         .install()
+///         .unwrap();
+///
+///     if let Err(report) = run() {
+///         eprintln!("Error: {:?}", report);
+///         color_eyre::issue::prompt(&report).unwrap();
+///         std::process::exit(1);
+///     }
+/// }
+/// ```
+pub fn prompt(report: &eyre::Report) -> io::Result<()> {
+    match report.handler().downcast_ref::<crate::Handler>() {
+        Some(handler) => handler.prompt_issue(&**report, |issue_section, prompt| {
+            issue_section.prompt(prompt)
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Ask whether to open the issue for `report`, reading the answer from
+/// `input` and writing the preview and question to `output`
+///
+/// Unlike [`prompt`], this asks even if stdin or stderr is not a terminal,
+/// for applications with their own console or user interface. An empty
+/// answer or the end of `input` is taken as no.
+///
+/// # Examples
+///
+/// ```rust
+/// use color_eyre::{eyre::eyre, issue::IssuePrompt};
+///
+/// color_eyre::config::HookBuilder::default()
+///     .issue_url("https://github.com/yaahc/jane-eyre/issues/new")
+///     .issue_prompt(IssuePrompt::new().opener(Box::new(|url| {
+///         println!("opening {}", url);
+///         Ok(())
+///     })))
+///     .install()
+///     .unwrap();
+///
+/// let report = eyre!("something went wrong");
+/// let mut output = Vec::new();
+/// color_eyre::issue::prompt_with(&report, &mut "y\n".as_bytes(), &mut output).unwrap();
+/// ```
+pub fn prompt_with<R, W>(report: &eyre::Report, input: &mut R, output: &mut W) -> io::Result<()>
+where
+    R: io::BufRead,
+    W: io::Write,
+{
+    match report.handler().downcast_ref::<crate::Handler>() {
+        Some(handler) => handler.prompt_issue(&**report, |issue_section, prompt| {
+            issue_section.prompt_with(prompt, input, output)
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Save an offline bug report bundle for `report` with the `IssueBundle`
+/// configured with `HookBuilder::issue_bundle`, and return the path of the
+/// new file
+///
+/// Fails if no bundle is configured, the `issue_filter` skips the report, or
+/// the report was not created with color-eyre's handler.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use color_eyre::{eyre::eyre, issue::IssueBundle};
+///
+/// color_eyre::config::HookBuilder::default()
+///     .issue_bundle(IssueBundle::new(std::env::temp_dir().join("my-app-reports")))
+///     .install()
+///     .unwrap();
+///
+/// let report = eyre!("failed to load the config");
+/// eprintln!("Error: {:?}", report);
+///
+/// match color_eyre::issue::save_bundle(&report) {
+///     Ok(path) => eprintln!("A bug report was saved to: {}", path.display()),
+///     Err(error) => eprintln!("Failed to save a bug report: {}", error),
+/// }
+/// ```
+pub fn save_bundle(report: &eyre::Report) -> io::Result<PathBuf> {
+    match report.handler().downcast_ref::<crate::Handler>() {
+        Some(handler) => handler.save_bundle(&**report),
+        None => Err(io::Error::other(
+            "the report was not created with color-eyre's handler",
+        )),
+    }
+}
+
+/// The command that opens `url` in the default browser on `os`, one of the
+/// values of `std::env::consts::OS`
+fn open_command(os: &str, url: &str) -> Command {
+    let mut command = match os {
+        "macos" => Command::new("open"),
+        // Going through `cmd /C start` would split the url at its first `&`
+        // and expand `%VAR%` sequences in it, so the url handler is called
+        // directly instead.
+        "windows" => {
+            let mut command = Command::new("rundll32");
+            command.arg("url.dll,FileProtocolHandler");
+            command
+        }
+        _ => Command::new("xdg-open"),
+    };
+    command.arg(url);
+    command
+}
+
+/// Open `url` with the platform's default browser
+fn open_url(url: &str) -> io::Result<()> {
+    let status = open_command(std::env::consts::OS, url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!(
+            "the browser exited with {}",
+            status
+        )))
+    }
+}
+
+/// The markup language of generated issue bodies
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Markup {
+    /// Markdown, using `<details>` blocks for long sections
+    Markdown,
+    /// Jira's wiki markup
+    JiraWiki,
+}
+
+/// GitHub's new issue form, e.g. `https://github.com/owner/repo/issues/new`
+#[derive(Debug, Default, Copy, Clone)]
+pub struct GitHub;
+
+impl IssueTracker for GitHub {
+    fn new_issue_url(&self, url: &str, issue: &NewIssue<'_>) -> Option<String> {
+        let labels = issue.labels.join(",");
+        let assignees = issue.assignees.join(",");
+        let mut params = vec![("title", issue.title), ("body", issue.body)];
+
+        if !labels.is_empty() {
+            params.push(("labels", &labels));
+        }
+
+        if !assignees.is_empty() {
+            params.push(("assignees", &assignees));
+        }
+
+        if let Some(template) = issue.template {
+            params.push(("template", template));
+        }
+
+        with_params(url, &params)
+    }
+
+    fn search_url(&self, url: &str, query: &str) -> Option<String> {
+        with_path(url, "/new", "", &[("q", &format!("is:issue {}", query))])
+    }
+}
+
+/// GitLab's new issue form, e.g. `https://gitlab.com/group/project/-/issues/new`
+///
+/// Labels and assignees are added as quick actions at the end of the
+/// description, and the template selects a description template.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct GitLab;
+
+impl IssueTracker for GitLab {
+    fn new_issue_url(&self, url: &str, issue: &NewIssue<'_>) -> Option<String> {
+        let mut description = issue.body.to_string();
+
+        if !issue.labels.is_empty() {
+            let labels: Vec<_> = issue
+                .labels
+                .iter()
+                .map(|label| format!("~\"{}\"", label))
+                .collect();
+            description.push_str(&format!("\n\n/label {}", labels.join(" ")));
+        }
+
+        if !issue.assignees.is_empty() {
+            let assignees: Vec<_> = issue
+                .assignees
+                .iter()
+                .map(|assignee| format!("@{}", assignee))
+                .collect();
+            description.push_str(&format!("\n/assign {}", assignees.join(" ")));
+        }
+
+        let mut params = vec![
+            ("issue[title]", issue.title),
+            ("issue[description]", &description),
+        ];
+
+        if let Some(template) = issue.template {
+            params.push(("issuable_template", template));
+        }
+
+        with_params(url, &params)
+    }
+
+    fn search_url(&self, url: &str, query: &str) -> Option<String> {
+        with_path(url, "/new", "", &[("search", query), ("state", "all")])
+    }
+}
+
+/// Gitea's and Forgejo's new issue form, e.g.
+/// `https://codeberg.org/owner/repo/issues/new`
+///
+/// Labels must be given as label ids, and assignees are not supported.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Gitea;
+
+impl IssueTracker for Gitea {
+    fn new_issue_url(&self, url: &str, issue: &NewIssue<'_>) -> Option<String> {
+        let labels = issue.labels.join(",");
+        let mut params = vec![("title", issue.title), ("body", issue.body)];
+
+        if !labels.is_empty() {
+            params.push(("labels", &labels));
+        }
+
+        if let Some(template) = issue.template {
+            params.push(("template", template));
+        }
+
+        with_params(url, &params)
+    }
+
+    fn search_url(&self, url: &str, query: &str) -> Option<String> {
+        with_path(url, "/new", "", &[("q", query), ("state", "all")])
+    }
+}
+
+/// Jira's create issue screen, e.g.
+/// `https://jira.example.com/secure/CreateIssueDetails!init.jspa`
+///
+/// Templates are not supported.
+#[derive(Debug, Clone)]
+pub struct Jira {
+    project_id: String,
+    issue_type: String,
+}
+
+impl Jira {
+    /// Create issues of the given issue type id in the project with the given
+    /// id
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use color_eyre::issue::Jira;
+    ///
+    /// color_eyre::config::HookBuilder::default()
+    ///     .issue_url("https://jira.example.com/secure/CreateIssueDetails!init.jspa")
+    ///     .issue_tracker(Jira::new(10000, 10004))
+    ///     .install()
+    ///     .unwrap();
+    /// ```
+    pub fn new(project_id: impl ToString, issue_type: impl ToString) -> Self {
+        Jira {
+            project_id: project_id.to_string(),
+            issue_type: issue_type.to_string(),
+        }
+    }
+}
+
+impl IssueTracker for Jira {
+    fn new_issue_url(&self, url: &str, issue: &NewIssue<'_>) -> Option<String> {
+        let mut params = vec![
+            ("pid", self.project_id.as_str()),
+            ("issuetype", &self.issue_type),
+            ("summary", issue.title),
+            ("description", issue.body),
+        ];
+
+        params.extend(issue.labels.iter().map(|label| ("labels", label.as_str())));
+
+        // Jira only supports a single assignee.
+        if let Some(assignee) = issue.assignees.first() {
+            params.push(("assignee", assignee));
+        }
+
+        with_params(url, &params)
+    }
+
+    fn search_url(&self, url: &str, query: &str) -> Option<String> {
+        let jql = format!(
+            "project = {} AND text ~ \"{}\"",
+            self.project_id,
+            query.replace('"', "\\\"")
+        );
+        with_path(
+            url,
+            "/secure/CreateIssueDetails!init.jspa",
+            "/issues/",
+            &[("jql", &jql)],
+        )
+    }
+
+    fn markup(&self) -> Markup {
+        Markup::JiraWiki
+    }
+}
+
+fn with_params(url: &str, params: &[(&str, &str)]) -> Option<String> {
+    Url::parse_with_params(url, params).ok().map(String::from)
+}
+
+/// Replace the `from` suffix of the path of `url` with `to`, and its query
+/// with `params`
+fn with_path(url: &str, from: &str, to: &str, params: &[(&str, &str)]) -> Option<String> {
+    let mut url = Url::parse(url).ok()?;
+    let path = url.path().strip_suffix(from)?.to_string();
+
+    url.set_path(&format!("{}{}", path, to));
+    url.set_query(None);
+    url.query_pairs_mut().extend_pairs(params);
+
+    Some(url.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::open_command;
+
+    #[test]
+    fn open_command_passes_the_url_as_is() {
+        let url = "https://github.com/owner/repo/issues/new\
+                   ?title=crash%3A+50%25&body=%25PATH%25+%26+more&labels=bug&template=bug.yml";
+
+        let windows = open_command("windows", url);
+        assert_eq!(windows.get_program(), "rundll32");
+        assert_eq!(
+            windows.get_args().collect::<Vec<_>>(),
+            ["url.dll,FileProtocolHandler", url]
+        );
+
+        let macos = open_command("macos", url);
+        assert_eq!(macos.get_program(), "open");
+        assert_eq!(macos.get_args().collect::<Vec<_>>(), [url]);
+
+        let linux = open_command("linux", url);
+        assert_eq!(linux.get_program(), "xdg-open");
+        assert_eq!(linux.get_args().collect::<Vec<_>>(), [url]);
+    }
+}
@@ -368,6 +368,7 @@ pub use eyre;
 pub use eyre::Report;
 #[doc(hidden)]
 pub use eyre::Result;
+pub use fingerprint::Fingerprint;
 pub use owo_colors;
 use section::help::HelpInfo;
 #[doc(hidden)]
@@ -379,10 +380,24 @@ use tracing_error::SpanTrace;
 pub use Handler as Context;
 
 pub mod config;
+#[cfg(feature = "crash-report")]
+#[cfg_attr(docsrs, doc(cfg(feature = "crash-report")))]
+pub mod crash_report;
+pub mod environment;
+mod fingerprint;
 mod fmt;
 mod handler;
+#[cfg(feature = "issue-url")]
+#[cfg_attr(docsrs, doc(cfg(feature = "issue-url")))]
+pub mod issue;
+mod module;
 pub(crate) mod private;
+mod redact;
+pub mod scope;
 pub mod section;
+#[cfg(feature = "raw-backtrace")]
+#[cfg_attr(docsrs, doc(cfg(feature = "raw-backtrace")))]
+pub mod symbolize;
 mod writers;
 
 /// A custom handler type for [`eyre::Report`] which provides colorful error
@@ -403,14 +418,35 @@ pub struct Handler {
     #[cfg(feature = "capture-spantrace")]
     span_trace: Option<SpanTrace>,
     sections: Vec<HelpInfo>,
+    error_limit: Option<usize>,
+    code: Option<String>,
+    docs_url: Option<String>,
     display_env_section: bool,
+    display_fingerprint: bool,
+    trace_layout: config::TraceLayout,
+    report_layout: Arc<config::ReportLayout>,
+    redactor: Arc<redact::Redactor>,
+    #[cfg(feature = "capture-spantrace")]
+    span_filter: Arc<config::SpanFilter>,
     #[cfg(feature = "issue-url")]
     issue_url: Option<String>,
     #[cfg(feature = "issue-url")]
+    issue_tracker: std::sync::Arc<dyn issue::IssueTracker>,
+    #[cfg(feature = "issue-url")]
+    issue_url_max_length: usize,
+    #[cfg(feature = "issue-url")]
+    issue_template: std::sync::Arc<issue::IssueTemplate>,
+    #[cfg(feature = "issue-url")]
+    issue_bundle: Option<std::sync::Arc<issue::IssueBundle>>,
+    #[cfg(feature = "issue-url")]
+    issue_prompt: Option<std::sync::Arc<issue::IssuePrompt>>,
+    #[cfg(feature = "issue-url")]
     issue_metadata:
         std::sync::Arc<Vec<(String, Box<dyn std::fmt::Display + Send + Sync + 'static>)>>,
     #[cfg(feature = "issue-url")]
     issue_filter: std::sync::Arc<config::IssueFilterCallback>,
+    #[cfg(feature = "crash-report")]
+    crash_reporter: Option<std::sync::Arc<crash_report::CrashReporter>>,
     theme: crate::config::Theme,
     #[cfg(feature = "track-caller")]
     location: Option<&'static std::panic::Location<'static>>,
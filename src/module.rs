@@ -0,0 +1,66 @@
+//! Lookup of the executable or shared object an instruction pointer belongs to
+use std::path::PathBuf;
+
+/// A loaded executable or shared object
+pub(crate) struct Module {
+    pub(crate) path: PathBuf,
+    pub(crate) base: usize,
+}
+
+impl Module {
+    /// Find the module that contains the given instruction pointer
+    #[cfg(unix)]
+    pub(crate) fn containing(ip: usize) -> Option<Module> {
+        use std::ffi::CStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let mut info = std::mem::MaybeUninit::<libc::Dl_info>::uninit();
+
+        // Safety: `dladdr` only reads the address and writes to `info`, which
+        // is only read after a successful lookup.
+        let info = unsafe {
+            if libc::dladdr(ip as *const libc::c_void, info.as_mut_ptr()) == 0 {
+                return None;
+            }
+
+            info.assume_init()
+        };
+
+        if info.dli_fname.is_null() {
+            return None;
+        }
+
+        // Safety: `dli_fname` is a nul terminated string owned by the dynamic
+        // loader that lives as long as the module stays loaded.
+        let name = unsafe { CStr::from_ptr(info.dli_fname) };
+        let name = std::ffi::OsStr::from_bytes(name.to_bytes());
+        let mut path = PathBuf::from(name);
+
+        // The dynamic loader reports the main executable by the name it was
+        // invoked with, which is not necessarily a usable path. Shared objects
+        // keep the path they were loaded from, even if it is relative.
+        if is_main_executable(name) {
+            if let Ok(exe) = std::env::current_exe() {
+                path = exe;
+            }
+        }
+
+        Some(Module {
+            path,
+            base: info.dli_fbase as usize,
+        })
+    }
+
+    /// Find the module that contains the given instruction pointer
+    #[cfg(not(unix))]
+    pub(crate) fn containing(_ip: usize) -> Option<Module> {
+        None
+    }
+}
+
+/// Whether `dladdr` named the main executable, which it reports either with
+/// an empty name or the name the program was invoked with
+#[cfg(unix)]
+fn is_main_executable(name: &std::ffi::OsStr) -> bool {
+    name.is_empty() || std::env::args_os().next().is_some_and(|arg0| arg0 == name)
+}
@@ -0,0 +1,498 @@
+//! Redaction of secrets and personal information in reports
+use std::fmt::{self, Display, Write};
+use std::ops::Range;
+
+/// A callback that replaces sensitive text in a line of a rendered report
+pub type RedactCallback = dyn Fn(&mut String) + Send + Sync + 'static;
+
+const REDACTED: &str = "[redacted]";
+
+/// Kinds of sensitive text that can be detected and redacted from reports
+///
+/// See `HookBuilder::add_redaction`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Redaction {
+    /// Bearer tokens, e.g. from `Authorization: Bearer <token>` headers
+    BearerTokens,
+    /// AWS access key ids and `aws_secret_access_key` values
+    AwsKeys,
+    /// Email addresses
+    Emails,
+    /// The current user's home directory, which is replaced by `~`, and the
+    /// user names in paths to other home directories
+    HomeDirectories,
+    /// IPv4 and IPv6 addresses. Dotted numbers that look like versions, e.g.
+    /// `version 1.2.3.4`, `my-crate-1.2.3.4` or `1.2.3.4-beta`, are kept
+    IpAddresses,
+}
+
+impl Redaction {
+    pub(crate) const ALL: [Redaction; 5] = [
+        Redaction::BearerTokens,
+        Redaction::AwsKeys,
+        Redaction::Emails,
+        Redaction::HomeDirectories,
+        Redaction::IpAddresses,
+    ];
+
+    fn apply(self, text: &mut String) {
+        if self == Redaction::HomeDirectories {
+            let plain = Plain::new(text);
+            let ranges = own_home_directory(&plain.text);
+            plain.replace(text, ranges, "~");
+        }
+
+        let plain = Plain::new(text);
+        let ranges = match self {
+            Redaction::BearerTokens => bearer_tokens(&plain.text),
+            Redaction::AwsKeys => aws_keys(&plain.text),
+            Redaction::Emails => emails(&plain.text),
+            Redaction::HomeDirectories => other_home_directories(&plain.text),
+            Redaction::IpAddresses => ip_addresses(&plain.text),
+        };
+
+        plain.replace(text, ranges, REDACTED);
+    }
+}
+
+/// The redaction rules applied to every rendered report
+#[derive(Default)]
+pub(crate) struct Redactor {
+    rules: Vec<Box<RedactCallback>>,
+}
+
+impl Redactor {
+    pub(crate) fn push(&mut self, rule: Box<RedactCallback>) {
+        self.rules.push(rule);
+    }
+
+    pub(crate) fn push_redaction(&mut self, redaction: Redaction) {
+        self.push(Box::new(move |text| redaction.apply(text)));
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Apply the rules to each line of `text`
+    #[cfg(any(feature = "issue-url", feature = "crash-report"))]
+    pub(crate) fn redact(&self, text: &mut String) {
+        if self.is_empty() {
+            return;
+        }
+
+        if !text.contains('\n') {
+            return self.redact_line(text);
+        }
+
+        let mut out = String::with_capacity(text.len());
+        for (n, line) in text.split('\n').enumerate() {
+            if n > 0 {
+                out.push('\n');
+            }
+
+            let mut line = line.to_string();
+            self.redact_line(&mut line);
+            out.push_str(&line);
+        }
+        *text = out;
+    }
+
+    fn redact_line(&self, line: &mut String) {
+        for rule in &self.rules {
+            rule(line);
+        }
+    }
+}
+
+/// Displays `T` with the redaction rules applied to each line of its output
+pub(crate) struct Redacted<'a, T>(pub(crate) &'a Redactor, pub(crate) T);
+
+impl<T> Display for Redacted<'_, T>
+where
+    T: Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self(redactor, inner) = self;
+
+        if redactor.is_empty() {
+            return inner.fmt(f);
+        }
+
+        let mut lines = RedactLines {
+            redactor,
+            f,
+            line: String::new(),
+        };
+        write!(&mut lines, "{}", inner)?;
+        lines.flush()
+    }
+}
+
+/// Buffers output until the end of each line and writes it redacted, so a
+/// report is never held in memory as a whole
+struct RedactLines<'a, 'b> {
+    redactor: &'a Redactor,
+    f: &'a mut fmt::Formatter<'b>,
+    line: String,
+}
+
+impl RedactLines<'_, '_> {
+    fn flush(&mut self) -> fmt::Result {
+        self.redactor.redact_line(&mut self.line);
+        self.f.write_str(&self.line)?;
+        self.line.clear();
+        Ok(())
+    }
+}
+
+impl Write for RedactLines<'_, '_> {
+    fn write_str(&mut self, mut s: &str) -> fmt::Result {
+        while let Some(i) = s.find('\n') {
+            self.line.push_str(&s[..i]);
+            self.flush()?;
+            self.f.write_char('\n')?;
+            s = &s[i + 1..];
+        }
+
+        self.line.push_str(s);
+        Ok(())
+    }
+}
+
+/// The length of the ANSI escape sequence at the start of `bytes`, if any
+fn ansi_len(bytes: &[u8]) -> Option<usize> {
+    if bytes.first() == Some(&0x1b) && bytes.get(1) == Some(&b'[') {
+        let len = bytes[2..]
+            .iter()
+            .position(|b| (0x40..=0x7e).contains(b))
+            .map_or(bytes.len(), |end| end + 3);
+        Some(len)
+    } else {
+        None
+    }
+}
+
+/// Text with its ANSI escape sequences removed, so detectors see styled
+/// text the way it is displayed
+struct Plain {
+    text: String,
+    /// the offset in the styled text of each byte of `text`, and its length
+    offsets: Vec<usize>,
+}
+
+impl Plain {
+    fn new(styled: &str) -> Self {
+        let bytes = styled.as_bytes();
+        let mut text = Vec::with_capacity(bytes.len());
+        let mut offsets = Vec::with_capacity(bytes.len() + 1);
+        let mut i = 0;
+
+        while i < bytes.len() {
+            match ansi_len(&bytes[i..]) {
+                Some(len) => i += len,
+                None => {
+                    text.push(bytes[i]);
+                    offsets.push(i);
+                    i += 1;
+                }
+            }
+        }
+        offsets.push(bytes.len());
+
+        Plain {
+            text: String::from_utf8(text).expect("only ASCII bytes were removed"),
+            offsets,
+        }
+    }
+
+    /// Replace the given ranges of the plain text in `styled`, keeping the
+    /// escape sequences inside of them so the styles that follow still apply.
+    /// The ranges must be sorted by their start, ranges overlapping a
+    /// previous range are skipped
+    fn replace(&self, styled: &mut String, ranges: Vec<Range<usize>>, with: &str) {
+        let ranges = disjoint(ranges)
+            .into_iter()
+            .map(|range| self.offsets[range.start]..self.offsets[range.end - 1] + 1);
+
+        let mut out = String::with_capacity(styled.len());
+        let mut end = 0;
+        for range in ranges {
+            out.push_str(&styled[end..range.start]);
+            out.push_str(with);
+
+            let mut rest = &styled[range.clone()];
+            while let Some(i) = rest.find('\u{1b}') {
+                let len = ansi_len(&rest.as_bytes()[i..]).unwrap_or(1);
+                if len > 1 {
+                    out.push_str(&rest[i..i + len]);
+                }
+                rest = &rest[i + len..];
+            }
+
+            end = range.end;
+        }
+        out.push_str(&styled[end..]);
+
+        *styled = out;
+    }
+}
+
+/// Remove ANSI escape sequences, e.g. from styled text that is written to
+/// an issue
+#[cfg(feature = "issue-url")]
+pub(crate) fn strip_ansi(text: &str) -> String {
+    Plain::new(text).text
+}
+
+/// The non-empty ranges that don't overlap a previous range, of ranges
+/// sorted by their start
+fn disjoint(ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    let mut end = 0;
+    ranges
+        .into_iter()
+        .filter(|range| {
+            let disjoint = range.start >= end && range.start < range.end;
+            if disjoint {
+                end = range.end;
+            }
+            disjoint
+        })
+        .collect()
+}
+
+/// The maximal runs of characters in `text` for which `f` returns true
+fn runs(text: &str, f: impl Fn(char) -> bool) -> Vec<Range<usize>> {
+    let mut out = Vec::new();
+    let mut start = None;
+
+    for (i, c) in text.char_indices() {
+        match (f(c), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                out.push(s..i);
+                start = None;
+            }
+            _ => (),
+        }
+    }
+
+    if let Some(s) = start {
+        out.push(s..text.len());
+    }
+
+    out
+}
+
+/// Whether `range` is not directly preceded or followed by a word character
+fn is_word(text: &str, range: &Range<usize>) -> bool {
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+    let before = text[..range.start].chars().next_back();
+    let after = text[range.end..].chars().next();
+
+    !matches!(before, Some(c) if is_word_char(c)) && !matches!(after, Some(c) if is_word_char(c))
+}
+
+/// The ranges of the values that follow any of the given keys
+fn values_after(
+    text: &str,
+    keys: &[&str],
+    is_value_char: impl Fn(char) -> bool,
+) -> Vec<Range<usize>> {
+    // ASCII lowercasing keeps byte offsets intact.
+    let lower = text.to_ascii_lowercase();
+    let mut out = Vec::new();
+
+    for key in keys {
+        let mut from = 0;
+        while let Some(i) = lower[from..].find(key) {
+            let end = from + i + key.len();
+            from = end;
+
+            let rest = &text[end..];
+            let start = end + rest.len()
+                - rest
+                    .trim_start_matches(&[' ', ':', '=', '"', '\''][..])
+                    .len();
+            if start == end {
+                continue;
+            }
+
+            let len = text[start..]
+                .find(|c| !is_value_char(c))
+                .unwrap_or(text.len() - start);
+            if len > 0 {
+                out.push(start..start + len);
+                from = start + len;
+            }
+        }
+    }
+
+    out.sort_by_key(|range| range.start);
+    out
+}
+
+fn bearer_tokens(text: &str) -> Vec<Range<usize>> {
+    let mut out = values_after(text, &["bearer"], |c| {
+        c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~' | '+' | '/' | '=')
+    });
+
+    // Leave prose like "the bearer of bad news" alone.
+    out.retain(|range| {
+        let token = &text[range.clone()];
+        token.len() >= 8 && !token.chars().all(|c| c.is_ascii_lowercase())
+    });
+    out
+}
+
+fn aws_keys(text: &str) -> Vec<Range<usize>> {
+    const PREFIXES: &[&str] = &[
+        "AKIA", "ASIA", "AGPA", "AIDA", "AROA", "ANPA", "ANVA", "AIPA",
+    ];
+
+    let mut out: Vec<_> = runs(text, |c| c.is_ascii_alphanumeric())
+        .into_iter()
+        .filter(|range| {
+            let word = &text[range.clone()];
+            word.len() == 20
+                && PREFIXES.iter().any(|prefix| word.starts_with(prefix))
+                && word
+                    .chars()
+                    .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+        })
+        .collect();
+
+    out.extend(values_after(
+        text,
+        &["aws_secret_access_key", "aws_session_token"],
+        |c| c.is_ascii_alphanumeric() || matches!(c, '/' | '+' | '='),
+    ));
+
+    out.sort_by_key(|range| range.start);
+    out
+}
+
+fn emails(text: &str) -> Vec<Range<usize>> {
+    let is_local_char =
+        |c: char| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '%' | '+' | '-');
+    let is_domain_char = |c: char| c.is_ascii_alphanumeric() || matches!(c, '.' | '-');
+
+    text.match_indices('@')
+        .filter_map(|(at, _)| {
+            let start = text[..at]
+                .char_indices()
+                .rev()
+                .find(|(_, c)| !is_local_char(*c))
+                .map_or(0, |(i, c)| i + c.len_utf8());
+            let end = text[at + 1..]
+                .find(|c| !is_domain_char(c))
+                .map_or(text.len(), |i| at + 1 + i);
+            let domain = text[at + 1..end].trim_end_matches('.');
+            let tld = domain.rsplit('.').next().unwrap_or("");
+
+            let valid = start < at
+                && domain.contains('.')
+                && tld.len() >= 2
+                && tld.chars().all(|c| c.is_ascii_alphabetic());
+
+            if valid {
+                Some(start..at + 1 + domain.len())
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+fn own_home_directory(text: &str) -> Vec<Range<usize>> {
+    let home = match std::env::var("HOME").or_else(|_| std::env::var("USERPROFILE")) {
+        Ok(home) => home,
+        Err(_) => return Vec::new(),
+    };
+    let home = home.trim_end_matches(&['/', '\\'][..]);
+
+    // An empty or root home directory would match every path.
+    if home.len() <= 1 {
+        return Vec::new();
+    }
+
+    text.match_indices(home)
+        .map(|(i, _)| i..i + home.len())
+        .filter(|range| {
+            !matches!(
+                text[range.end..].chars().next(),
+                Some(c) if c.is_alphanumeric() || matches!(c, '_' | '-' | '.')
+            )
+        })
+        .collect()
+}
+
+fn other_home_directories(text: &str) -> Vec<Range<usize>> {
+    let mut out: Vec<_> = ["/home/", "/Users/", "\\Users\\"]
+        .iter()
+        .flat_map(|prefix| {
+            text.match_indices(prefix).filter_map(move |(i, _)| {
+                let start = i + prefix.len();
+                let len = text[start..]
+                    .find(|c: char| c.is_whitespace() || matches!(c, '/' | '\\' | '"' | '\'' | ':'))
+                    .unwrap_or(text.len() - start);
+
+                if len > 0 && &text[start..start + len] != REDACTED {
+                    Some(start..start + len)
+                } else {
+                    None
+                }
+            })
+        })
+        .collect();
+
+    out.sort_by_key(|range| range.start);
+    out
+}
+
+fn ip_addresses(text: &str) -> Vec<Range<usize>> {
+    let v4 = runs(text, |c| c.is_ascii_digit() || c == '.')
+        .into_iter()
+        .filter(|range| {
+            let candidate = &text[range.clone()];
+            is_word(text, range)
+                && !is_version(text, range)
+                && candidate.split('.').count() == 4
+                && candidate.parse::<std::net::Ipv4Addr>().is_ok()
+        });
+
+    // Requiring a digit keeps paths like `face::bad` from being mistaken for
+    // addresses.
+    let v6 = runs(text, |c| c.is_ascii_hexdigit() || c == ':')
+        .into_iter()
+        .filter(|range| {
+            let candidate = &text[range.clone()];
+            is_word(text, range)
+                && candidate.matches(':').count() >= 2
+                && candidate.chars().any(|c| c.is_ascii_digit())
+                && candidate.parse::<std::net::Ipv6Addr>().is_ok()
+        });
+
+    let mut out: Vec<_> = v4.chain(v6).collect();
+    out.sort_by_key(|range| range.start);
+    out
+}
+
+/// Whether the dotted numbers at `range` look like a version rather than an
+/// address, e.g. `version 1.2.3.4`, `my-crate-1.2.3.4` or `1.2.3.4-beta`
+fn is_version(text: &str, range: &Range<usize>) -> bool {
+    let before = &text[..range.start];
+    let after = &text[range.end..];
+
+    let keyword = before
+        .trim_end_matches(&[' ', ':', '=', '"', '\''][..])
+        .rsplit(|c: char| !c.is_alphanumeric())
+        .next()
+        .unwrap_or("")
+        .to_ascii_lowercase();
+
+    matches!(keyword.as_str(), "version" | "ver" | "release")
+        || before.ends_with('-')
+        || after.starts_with(&['-', '+'][..])
+}
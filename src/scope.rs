@@ -0,0 +1,329 @@
+//! Context attached to the panics and reports of the current scope
+//!
+//! `HookBuilder::panic_section` is set once for the whole program. The
+//! sections of this module are instead active while a guard is alive, so a
+//! worker can say which job, file or request it was processing when it
+//! panicked or returned an error:
+//!
+//! ```rust
+//! use color_eyre::scope::with_panic_section;
+//!
+//! fn process(job_id: u64) {
+//!     let _job = with_panic_section("job", job_id);
+//!     // A panic here prints `job: 42` in the panic report.
+//! }
+//! # process(42);
+//! ```
+//!
+//! Sections are stored per thread, so the guards can't be held across an
+//! `.await` of a task that may move between threads. Wrap the future with
+//! `in_panic_section_async` or `in_report_section_async` instead, which add
+//! the section on whichever thread polls it, for the duration of each poll.
+use crate::section::{help::HelpInfo, FieldValue};
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::thread::LocalKey;
+
+type Sections = RefCell<Vec<(usize, String, FieldValue)>>;
+
+thread_local! {
+    static PANIC_SECTIONS: Sections = const { RefCell::new(Vec::new()) };
+    static REPORT_SECTIONS: Sections = const { RefCell::new(Vec::new()) };
+    static NEXT_ID: Cell<usize> = const { Cell::new(0) };
+}
+
+/// A section of one of the thread-local stacks, removed when dropped
+#[derive(Debug)]
+struct Entry {
+    stack: &'static LocalKey<Sections>,
+    id: usize,
+    // The section belongs to the thread it was added on.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Entry {
+    fn push(stack: &'static LocalKey<Sections>, key: String, value: FieldValue) -> Self {
+        let id = NEXT_ID.with(|next| {
+            let id = next.get();
+            next.set(id + 1);
+            id
+        });
+
+        stack.with(|sections| sections.borrow_mut().push((id, key, value)));
+
+        Entry {
+            stack,
+            id,
+            _not_send: PhantomData,
+        }
+    }
+}
+
+impl Drop for Entry {
+    fn drop(&mut self) {
+        let _ = self.stack.try_with(|sections| {
+            sections.borrow_mut().retain(|(id, ..)| *id != self.id);
+        });
+    }
+}
+
+/// A future polled with a section added to one of the thread-local stacks
+struct Scoped<F> {
+    stack: &'static LocalKey<Sections>,
+    key: String,
+    value: FieldValue,
+    future: Pin<Box<F>>,
+}
+
+impl<F: Future> Scoped<F> {
+    fn poll(&mut self, cx: &mut Context<'_>) -> Poll<F::Output> {
+        let _entry = Entry::push(self.stack, self.key.clone(), self.value.clone());
+        self.future.as_mut().poll(cx)
+    }
+}
+
+impl<F> fmt::Debug for Scoped<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Scoped")
+            .field("key", &self.key)
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+/// The sections of a stack, in the order they were added
+fn snapshot(stack: &'static LocalKey<Sections>) -> Vec<HelpInfo> {
+    stack
+        .try_with(|sections| {
+            sections
+                .borrow()
+                .iter()
+                .map(|(_, key, value)| HelpInfo::Field(key.clone(), value.clone()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Removes its section from the panic sections of the current thread when
+/// dropped, see `with_panic_section`
+#[must_use = "the section is removed when the guard is dropped"]
+#[derive(Debug)]
+pub struct PanicSectionGuard {
+    _entry: Entry,
+}
+
+/// Add a `key: value` section to the panic reports of the current thread
+/// until the returned guard is dropped
+///
+/// Active sections are printed in a `Context:` table in the order they were
+/// added, and are added to the metadata of generated issues and the fields of
+/// crash reports.
+///
+/// # Examples
+///
+/// ```rust
+/// use color_eyre::scope::with_panic_section;
+///
+/// for path in ["a.csv", "b.csv"] {
+///     let _file = with_panic_section("file", path);
+///     // ...
+/// }
+/// ```
+pub fn with_panic_section<K, V>(key: K, value: V) -> PanicSectionGuard
+where
+    K: Into<String>,
+    V: Into<FieldValue>,
+{
+    PanicSectionGuard {
+        _entry: Entry::push(&PANIC_SECTIONS, key.into(), value.into()),
+    }
+}
+
+/// Call `f` with a `key: value` section added to the panic reports of the
+/// current thread, see `with_panic_section`
+///
+/// # Examples
+///
+/// ```rust
+/// use color_eyre::scope::in_panic_section;
+///
+/// let rows = in_panic_section("request", "GET /users", || 3);
+/// assert_eq!(rows, 3);
+/// ```
+pub fn in_panic_section<K, V, F, R>(key: K, value: V, f: F) -> R
+where
+    K: Into<String>,
+    V: Into<FieldValue>,
+    F: FnOnce() -> R,
+{
+    let _guard = with_panic_section(key, value);
+    f()
+}
+
+/// A future with a section added to the panic reports of the thread polling
+/// it, see `in_panic_section_async`
+#[must_use = "futures do nothing unless polled"]
+#[derive(Debug)]
+pub struct PanicSectionFuture<F> {
+    inner: Scoped<F>,
+}
+
+impl<F: Future> Future for PanicSectionFuture<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.inner.poll(cx)
+    }
+}
+
+/// Wrap `future` so that a `key: value` section is added to the panic reports
+/// of the thread polling it while each call to `poll` runs
+///
+/// Unlike `with_panic_section`, the returned future is `Send` when `future`
+/// is, so it can be spawned on a multi-threaded executor.
+///
+/// # Examples
+///
+/// ```rust
+/// use color_eyre::scope::in_panic_section_async;
+///
+/// async fn handle(request: &str) -> usize {
+///     in_panic_section_async("request", request.to_string(), async {
+///         // A panic here prints `request: GET /users` in the panic report,
+///         // whichever thread the task is polled on.
+///         3
+///     })
+///     .await
+/// }
+/// # let _ = handle("GET /users");
+/// ```
+pub fn in_panic_section_async<K, V, F>(key: K, value: V, future: F) -> PanicSectionFuture<F>
+where
+    K: Into<String>,
+    V: Into<FieldValue>,
+    F: Future,
+{
+    PanicSectionFuture {
+        inner: Scoped {
+            stack: &PANIC_SECTIONS,
+            key: key.into(),
+            value: value.into(),
+            future: Box::pin(future),
+        },
+    }
+}
+
+/// Removes its section from the report sections of the current thread when
+/// dropped, see `with_report_section`
+#[must_use = "the section is removed when the guard is dropped"]
+#[derive(Debug)]
+pub struct ReportSectionGuard {
+    _entry: Entry,
+}
+
+/// Add a `key: value` field to every `Report` created on the current thread
+/// until the returned guard is dropped
+///
+/// The active sections are copied into each report when it is created, as if
+/// attached with `Section::field`, so they are printed in the `Context:`
+/// table even after the guard is dropped. Unlike a `SpanTrace`, this does
+/// not need `tracing` or the `capture-spantrace` feature.
+///
+/// # Examples
+///
+/// ```rust
+/// use color_eyre::{eyre::eyre, scope::with_report_section};
+///
+/// color_eyre::install().unwrap();
+///
+/// let report = {
+///     let _request = with_report_section("request_id", "4f2a9c");
+///     eyre!("failed to load the user")
+/// };
+///
+/// assert!(format!("{:?}", report).contains("request_id: 4f2a9c"));
+/// ```
+pub fn with_report_section<K, V>(key: K, value: V) -> ReportSectionGuard
+where
+    K: Into<String>,
+    V: Into<FieldValue>,
+{
+    ReportSectionGuard {
+        _entry: Entry::push(&REPORT_SECTIONS, key.into(), value.into()),
+    }
+}
+
+/// Call `f` with a `key: value` field added to every `Report` created on the
+/// current thread, see `with_report_section`
+pub fn in_report_section<K, V, F, R>(key: K, value: V, f: F) -> R
+where
+    K: Into<String>,
+    V: Into<FieldValue>,
+    F: FnOnce() -> R,
+{
+    let _guard = with_report_section(key, value);
+    f()
+}
+
+/// A future with a field added to every `Report` created by the thread
+/// polling it, see `in_report_section_async`
+#[must_use = "futures do nothing unless polled"]
+#[derive(Debug)]
+pub struct ReportSectionFuture<F> {
+    inner: Scoped<F>,
+}
+
+impl<F: Future> Future for ReportSectionFuture<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.inner.poll(cx)
+    }
+}
+
+/// Wrap `future` so that a `key: value` field is added to every `Report`
+/// created by the thread polling it while each call to `poll` runs, see
+/// `with_report_section`
+///
+/// # Examples
+///
+/// ```rust
+/// use color_eyre::{eyre::{eyre, Report}, scope::in_report_section_async};
+///
+/// async fn load_user(request_id: &str) -> Result<(), Report> {
+///     in_report_section_async("request_id", request_id.to_string(), async {
+///         Err(eyre!("failed to load the user"))
+///     })
+///     .await
+/// }
+/// # let _ = load_user("4f2a9c");
+/// ```
+pub fn in_report_section_async<K, V, F>(key: K, value: V, future: F) -> ReportSectionFuture<F>
+where
+    K: Into<String>,
+    V: Into<FieldValue>,
+    F: Future,
+{
+    ReportSectionFuture {
+        inner: Scoped {
+            stack: &REPORT_SECTIONS,
+            key: key.into(),
+            value: value.into(),
+            future: Box::pin(future),
+        },
+    }
+}
+
+/// The panic sections active on the current thread, as field sections
+pub(crate) fn panic_sections() -> Vec<HelpInfo> {
+    snapshot(&PANIC_SECTIONS)
+}
+
+/// The report sections active on the current thread, as field sections
+pub(crate) fn report_sections() -> Vec<HelpInfo> {
+    snapshot(&REPORT_SECTIONS)
+}
@@ -0,0 +1,145 @@
+//! Provides an extension trait for collecting the errors of an iterator into a
+//! single report.
+use crate::{eyre::Report, section::help::HelpInfo};
+use std::fmt::Display;
+use std::iter::FromIterator;
+
+/// The message and limit of the report built by
+/// `CollectErrors::collect_errors`
+///
+/// Strings convert into an `Aggregate` with that message and no limit.
+#[derive(Debug, Clone)]
+pub struct Aggregate {
+    message: String,
+    limit: Option<usize>,
+}
+
+impl Aggregate {
+    /// Construct an `Aggregate` whose report has `message` as its error
+    pub fn new(message: impl Display) -> Self {
+        Self {
+            message: message.to_string(),
+            limit: None,
+        }
+    }
+
+    /// Print at most `limit` of the collected errors, followed by a line
+    /// like `... and 37 more`
+    ///
+    /// Every error is still attached to the report, see `Handler::errors`.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+}
+
+impl From<&str> for Aggregate {
+    fn from(message: &str) -> Self {
+        Self::new(message)
+    }
+}
+
+impl From<String> for Aggregate {
+    fn from(message: String) -> Self {
+        Self::new(message)
+    }
+}
+
+/// An extension trait for collecting an iterator of `Result`s into either
+/// all of its `Ok` values or a single `Report` of all of its errors
+///
+/// # Examples
+///
+/// ```rust
+/// use color_eyre::{eyre::Report, section::{Aggregate, CollectErrors}, Handler};
+///
+/// color_eyre::install().unwrap();
+///
+/// let rows = ["1", "two", "3", "four"];
+/// let report = rows
+///     .iter()
+///     .map(|row| row.parse::<u32>())
+///     .collect_errors::<Vec<_>, _>(Aggregate::new("failed to parse the rows").limit(10))
+///     .unwrap_err();
+///
+/// let handler = report.handler().downcast_ref::<Handler>().unwrap();
+/// assert_eq!(report.to_string(), "failed to parse the rows");
+/// assert_eq!(handler.errors().count(), 2);
+///
+/// let numbers: Vec<u32> = ["1", "2"]
+///     .iter()
+///     .map(|row| row.parse::<u32>())
+///     .collect_errors("failed to parse the rows")?;
+/// assert_eq!(numbers, [1, 2]);
+/// # Ok::<_, Report>(())
+/// ```
+pub trait CollectErrors<T, E>: Iterator<Item = Result<T, E>> + Sized {
+    /// Collect the `Ok` values, or return a report with `aggregate`'s message
+    /// and every error attached with `Section::error`
+    ///
+    /// If the report isn't created with color-eyre's handler, the messages of
+    /// the errors are the text of its source instead, one per line.
+    #[cfg_attr(feature = "track-caller", track_caller)]
+    fn collect_errors<C, A>(self, aggregate: A) -> Result<C, Report>
+    where
+        C: FromIterator<T>,
+        A: Into<Aggregate>,
+        E: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
+    {
+        let mut errors = Vec::new();
+        let values = self
+            .filter_map(|result| match result {
+                Ok(value) => Some(value),
+                Err(error) => {
+                    errors.push(error.into());
+                    None
+                }
+            })
+            .collect();
+
+        if errors.is_empty() {
+            return Ok(values);
+        }
+
+        let aggregate = aggregate.into();
+        let mut report = Report::msg(aggregate.message.clone());
+
+        match report.handler_mut().downcast_mut::<crate::Handler>() {
+            Some(handler) => {
+                handler.error_limit = aggregate.limit;
+
+                for error in errors {
+                    let origin = handler.error_origin();
+                    handler
+                        .sections
+                        .push(HelpInfo::Error(error, handler.theme, origin));
+                }
+
+                Err(report)
+            }
+            // Other handlers can't hold the errors, so they are kept as the
+            // text of the report's source instead.
+            None => {
+                Err(Report::msg(error_list(&errors, aggregate.limit)).wrap_err(aggregate.message))
+            }
+        }
+    }
+}
+
+/// The messages of `errors`, one per line, with at most `limit` of them
+/// followed by a line like `... and 37 more`
+fn error_list(
+    errors: &[Box<dyn std::error::Error + Send + Sync + 'static>],
+    limit: Option<usize>,
+) -> String {
+    let limit = limit.unwrap_or(usize::MAX);
+    let mut lines: Vec<_> = errors.iter().take(limit).map(ToString::to_string).collect();
+
+    if errors.len() > limit {
+        lines.push(format!("... and {} more", errors.len() - limit));
+    }
+
+    lines.join("\n")
+}
+
+impl<I, T, E> CollectErrors<T, E> for I where I: Iterator<Item = Result<T, E>> {}
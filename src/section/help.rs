@@ -5,7 +5,7 @@ use crate::{
     Section,
 };
 use indenter::indented;
-use owo_colors::OwoColorize;
+use owo_colors::{OwoColorize, Style};
 use std::fmt::Write;
 use std::fmt::{self, Display};
 
@@ -118,18 +118,92 @@ impl Section for Report {
         self
     }
 
+    fn field<K, V>(mut self, key: K, value: V) -> Self::Return
+    where
+        K: Into<String>,
+        V: Into<FieldValue>,
+    {
+        if let Some(handler) = self.handler_mut().downcast_mut::<crate::Handler>() {
+            handler
+                .sections
+                .push(HelpInfo::Field(key.into(), value.into()));
+        }
+
+        self
+    }
+
+    fn with_field<K, V, F>(mut self, key: K, value: F) -> Self::Return
+    where
+        K: Into<String>,
+        V: Into<FieldValue>,
+        F: FnOnce() -> V,
+    {
+        if let Some(handler) = self.handler_mut().downcast_mut::<crate::Handler>() {
+            handler
+                .sections
+                .push(HelpInfo::Field(key.into(), value().into()));
+        }
+
+        self
+    }
+
+    fn code<C>(mut self, code: C) -> Self::Return
+    where
+        C: Into<String>,
+    {
+        if let Some(handler) = self.handler_mut().downcast_mut::<crate::Handler>() {
+            handler.code = Some(code.into());
+        }
+
+        self
+    }
+
+    fn labeled<D>(mut self, kind: DefinedKind, body: D) -> Self::Return
+    where
+        D: Display + Send + Sync + 'static,
+    {
+        if let Some(handler) = self.handler_mut().downcast_mut::<crate::Handler>() {
+            let theme = handler.theme;
+            handler
+                .sections
+                .push(HelpInfo::Defined(kind, Box::new(body), theme));
+        }
+
+        self
+    }
+
+    fn with_labeled<D, F>(mut self, kind: DefinedKind, body: F) -> Self::Return
+    where
+        D: Display + Send + Sync + 'static,
+        F: FnOnce() -> D,
+    {
+        if let Some(handler) = self.handler_mut().downcast_mut::<crate::Handler>() {
+            let theme = handler.theme;
+            handler
+                .sections
+                .push(HelpInfo::Defined(kind, Box::new(body()), theme));
+        }
+
+        self
+    }
+
+    #[cfg_attr(feature = "track-caller", track_caller)]
     fn error<E2>(mut self, error: E2) -> Self::Return
     where
         E2: std::error::Error + Send + Sync + 'static,
     {
         if let Some(handler) = self.handler_mut().downcast_mut::<crate::Handler>() {
             let error = error.into();
-            handler.sections.push(HelpInfo::Error(error, handler.theme));
+            let origin = handler.error_origin();
+            handler
+                .sections
+                .push(HelpInfo::Error(error, handler.theme, origin));
         }
 
         self
     }
 
+    #[cfg_attr(feature = "track-caller", track_caller)]
     fn with_error<E2, F>(mut self, error: F) -> Self::Return
     where
         F: FnOnce() -> E2,
@@ -137,7 +211,10 @@ impl Section for Report {
     {
         if let Some(handler) = self.handler_mut().downcast_mut::<crate::Handler>() {
             let error = error().into();
-            handler.sections.push(HelpInfo::Error(error, handler.theme));
+            let origin = handler.error_origin();
+            handler
+                .sections
+                .push(HelpInfo::Error(error, handler.theme, origin));
         }
 
         self
@@ -218,6 +295,51 @@ where
             .map_err(|report| report.section(section))
     }
 
+    fn field<K, V>(self, key: K, value: V) -> Self::Return
+    where
+        K: Into<String>,
+        V: Into<FieldValue>,
+    {
+        self.map_err(|error| error.into())
+            .map_err(|report| report.field(key, value))
+    }
+
+    fn with_field<K, V, F>(self, key: K, value: F) -> Self::Return
+    where
+        K: Into<String>,
+        V: Into<FieldValue>,
+        F: FnOnce() -> V,
+    {
+        self.map_err(|error| error.into())
+            .map_err(|report| report.field(key, value()))
+    }
+
+    fn code<C>(self, code: C) -> Self::Return
+    where
+        C: Into<String>,
+    {
+        self.map_err(|error| error.into())
+            .map_err(|report| report.code(code))
+    }
+
+    fn labeled<D>(self, kind: DefinedKind, body: D) -> Self::Return
+    where
+        D: Display + Send + Sync + 'static,
+    {
+        self.map_err(|error| error.into())
+            .map_err(|report| report.labeled(kind, body))
+    }
+
+    fn with_labeled<D, F>(self, kind: DefinedKind, body: F) -> Self::Return
+    where
+        D: Display + Send + Sync + 'static,
+        F: FnOnce() -> D,
+    {
+        self.map_err(|error| error.into())
+            .map_err(|report| report.labeled(kind, body()))
+    }
+
+    #[cfg_attr(feature = "track-caller", track_caller)]
     fn error<E2>(self, error: E2) -> Self::Return
     where
         E2: std::error::Error + Send + Sync + 'static,
@@ -226,6 +348,7 @@ where
             .map_err(|report| report.error(error))
     }
 
+    #[cfg_attr(feature = "track-caller", track_caller)]
     fn with_error<E2, F>(self, error: F) -> Self::Return
     where
         F: FnOnce() -> E2,
@@ -237,11 +360,24 @@ where
 }
 
 pub(crate) enum HelpInfo {
-    Error(Box<dyn std::error::Error + Send + Sync + 'static>, Theme),
+    Error(
+        Box<dyn std::error::Error + Send + Sync + 'static>,
+        Theme,
+        ErrorOrigin,
+    ),
     Custom(Box<dyn Display + Send + Sync + 'static>),
     Note(Box<dyn Display + Send + Sync + 'static>, Theme),
     Warning(Box<dyn Display + Send + Sync + 'static>, Theme),
     Suggestion(Box<dyn Display + Send + Sync + 'static>, Theme),
+    Field(String, FieldValue),
+    Defined(DefinedKind, Box<dyn Display + Send + Sync + 'static>, Theme),
+}
+
+/// Where an error section was attached, see `ReportLayout::error_tree`
+pub(crate) struct ErrorOrigin {
+    #[cfg(feature = "track-caller")]
+    pub(crate) location: &'static std::panic::Location<'static>,
+    pub(crate) backtrace: Option<Box<backtrace::Backtrace>>,
 }
 
 impl Display for HelpInfo {
@@ -263,7 +399,11 @@ impl Display for HelpInfo {
                 suggestion
             ),
             HelpInfo::Custom(section) => write!(f, "{}", section),
-            HelpInfo::Error(error, theme) => {
+            HelpInfo::Defined(kind, section, theme) => {
+                write!(f, "{}: {}", LabelDisplay(kind, theme), section)
+            }
+            HelpInfo::Field(key, value) => write!(f, "{}: {}", key, value),
+            HelpInfo::Error(error, theme, _) => {
                 // a lot here
                 let errors = std::iter::successors(
                     Some(error.as_ref() as &(dyn std::error::Error + 'static)),
@@ -302,6 +442,323 @@ impl fmt::Debug for HelpInfo {
                 .field(&format_args!("{}", custom))
                 .finish(),
             HelpInfo::Error(error, ..) => f.debug_tuple("Error").field(error).finish(),
+            HelpInfo::Field(key, value) => f.debug_tuple("Field").field(key).field(value).finish(),
+            HelpInfo::Defined(kind, section, _) => f
+                .debug_tuple(kind.label)
+                .field(&format_args!("{}", section))
+                .finish(),
+        }
+    }
+}
+
+impl HelpInfo {
+    /// Replace the body of the section, keeping its kind and theme
+    pub(crate) fn replace(&mut self, body: Box<dyn Display + Send + Sync + 'static>) {
+        match self {
+            HelpInfo::Error(error, ..) => *error = Box::new(DisplayError(body)),
+            HelpInfo::Custom(section)
+            | HelpInfo::Defined(_, section, _)
+            | HelpInfo::Note(section, _)
+            | HelpInfo::Warning(section, _)
+            | HelpInfo::Suggestion(section, _) => *section = body,
+            HelpInfo::Field(_, value) => *value = FieldValue::String(body.to_string()),
+        }
+    }
+
+    pub(crate) fn kind(&self) -> SectionKind {
+        match self {
+            HelpInfo::Error(..) => SectionKind::Error,
+            HelpInfo::Custom(..) => SectionKind::Custom,
+            HelpInfo::Note(..) => SectionKind::Note,
+            HelpInfo::Warning(..) => SectionKind::Warning,
+            HelpInfo::Suggestion(..) => SectionKind::Suggestion,
+            HelpInfo::Field(..) => SectionKind::Field,
+            HelpInfo::Defined(kind, ..) => SectionKind::Defined(kind.label),
+        }
+    }
+}
+
+/// An error without a source, for replacing the error of a section
+struct DisplayError(Box<dyn Display + Send + Sync + 'static>);
+
+impl Display for DisplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl fmt::Debug for DisplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for DisplayError {}
+
+/// The kind of a section attached to a report
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SectionKind {
+    /// An error attached with `Section::error`
+    Error,
+    /// A section attached with `Section::section`
+    Custom,
+    /// A note attached with `Section::note`
+    Note,
+    /// A warning attached with `Section::warning`
+    Warning,
+    /// A suggestion attached with `Section::suggestion`
+    Suggestion,
+    /// A key/value field attached with `Section::field`
+    Field,
+    /// A section of a `DefinedKind` with the given label, attached with
+    /// `Section::labeled`
+    Defined(&'static str),
+}
+
+/// A user-defined kind of section, with its own label, style, icon and
+/// priority
+///
+/// Sections of a defined kind are attached with `Section::labeled` and are
+/// printed like notes, prefixed with the styled label. The priority places
+/// them in the report like the priorities of a `ReportLayout`, which can also
+/// override it with `ReportPart::Section(SectionKind::Defined(label))`.
+///
+/// # Examples
+///
+/// ```rust
+/// use color_eyre::{eyre::eyre, owo_colors::style, section::DefinedKind, Section};
+///
+/// fn docs() -> DefinedKind {
+///     DefinedKind::new("Docs").style(style().cyan()).icon("📖")
+/// }
+///
+/// let report = eyre!("failed to parse the config")
+///     .labeled(docs(), "https://example.com/docs/config");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct DefinedKind {
+    pub(crate) label: &'static str,
+    pub(crate) style: Style,
+    pub(crate) icon: Option<&'static str>,
+    pub(crate) priority: i32,
+}
+
+impl DefinedKind {
+    /// Construct a kind of section printed with `label`, unstyled and with
+    /// the priority of notes
+    pub fn new(label: &'static str) -> Self {
+        Self {
+            label,
+            style: Style::new(),
+            icon: None,
+            priority: 30,
+        }
+    }
+
+    /// Set the style of the label, used when the theme enables
+    /// `Theme::defined_kinds`
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Set an icon printed before the label
+    pub fn icon(mut self, icon: &'static str) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    /// Set the priority of the sections of this kind in the report, see
+    /// `ReportLayout`
+    pub fn priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// The label of the kind
+    pub fn label(&self) -> &'static str {
+        self.label
+    }
+}
+
+/// A label of a `DefinedKind` prefixed with its icon, styled unless the theme
+/// disables `Theme::defined_kinds`
+pub(crate) struct LabelDisplay<'a>(pub(crate) &'a DefinedKind, pub(crate) &'a Theme);
+
+impl Display for LabelDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let LabelDisplay(kind, theme) = self;
+
+        if let Some(icon) = kind.icon {
+            write!(f, "{} ", icon)?;
+        }
+
+        if theme.defined_kinds {
+            write!(f, "{}", kind.label.style(kind.style))
+        } else {
+            write!(f, "{}", kind.label)
+        }
+    }
+}
+
+/// A view of a section attached to a report, see `Handler::sections`
+///
+/// Displays the text of the section, without its label and color codes
+/// added by the theme.
+#[derive(Clone, Copy)]
+pub struct SectionRef<'a>(pub(crate) &'a HelpInfo);
+
+impl<'a> SectionRef<'a> {
+    /// The kind of the section
+    pub fn kind(&self) -> SectionKind {
+        self.0.kind()
+    }
+
+    /// The error attached with `Section::error`, or `None` for other kinds of
+    /// sections
+    pub fn error(&self) -> Option<&'a (dyn std::error::Error + Send + Sync + 'static)> {
+        match self.0 {
+            HelpInfo::Error(error, ..) => Some(&**error),
+            _ => None,
+        }
+    }
+
+    /// The key and value of a field attached with `Section::field`, or `None`
+    /// for other kinds of sections
+    pub fn field(&self) -> Option<(&'a str, &'a FieldValue)> {
+        match self.0 {
+            HelpInfo::Field(key, value) => Some((key, value)),
+            _ => None,
+        }
+    }
+}
+
+impl Display for SectionRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            HelpInfo::Error(error, ..) => write!(f, "{}", error),
+            HelpInfo::Custom(section) | HelpInfo::Defined(_, section, _) => {
+                write!(f, "{}", section)
+            }
+            HelpInfo::Note(section, _)
+            | HelpInfo::Warning(section, _)
+            | HelpInfo::Suggestion(section, _) => write!(f, "{}", section),
+            HelpInfo::Field(key, value) => write!(f, "{}: {}", key, value),
+        }
+    }
+}
+
+impl fmt::Debug for SectionRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.0, f)
+    }
+}
+
+/// The value of a field attached with `Section::field`
+///
+/// Created from strings, paths, integers, floats, booleans and chars with
+/// `From`.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum FieldValue {
+    /// A string
+    String(String),
+    /// A signed integer
+    Int(i64),
+    /// An unsigned integer
+    UInt(u64),
+    /// A floating point number
+    Float(f64),
+    /// A boolean
+    Bool(bool),
+}
+
+impl Display for FieldValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldValue::String(value) => value.fmt(f),
+            FieldValue::Int(value) => value.fmt(f),
+            FieldValue::UInt(value) => value.fmt(f),
+            FieldValue::Float(value) => value.fmt(f),
+            FieldValue::Bool(value) => value.fmt(f),
         }
     }
 }
+
+macro_rules! impl_from {
+    ($variant:ident($ty:ty): $($from:ty),*) => {
+        $(
+            impl From<$from> for FieldValue {
+                fn from(value: $from) -> Self {
+                    FieldValue::$variant(value as $ty)
+                }
+            }
+        )*
+    };
+}
+
+impl_from!(Int(i64): i8, i16, i32, i64, isize);
+impl_from!(UInt(u64): u8, u16, u32, u64, usize);
+impl_from!(Float(f64): f32, f64);
+
+impl From<bool> for FieldValue {
+    fn from(value: bool) -> Self {
+        FieldValue::Bool(value)
+    }
+}
+
+impl From<char> for FieldValue {
+    fn from(value: char) -> Self {
+        FieldValue::String(value.to_string())
+    }
+}
+
+impl From<String> for FieldValue {
+    fn from(value: String) -> Self {
+        FieldValue::String(value)
+    }
+}
+
+impl From<&str> for FieldValue {
+    fn from(value: &str) -> Self {
+        FieldValue::String(value.to_string())
+    }
+}
+
+impl From<&String> for FieldValue {
+    fn from(value: &String) -> Self {
+        FieldValue::String(value.clone())
+    }
+}
+
+impl From<&std::path::Path> for FieldValue {
+    fn from(value: &std::path::Path) -> Self {
+        FieldValue::String(value.display().to_string())
+    }
+}
+
+impl From<std::path::PathBuf> for FieldValue {
+    fn from(value: std::path::PathBuf) -> Self {
+        FieldValue::String(value.display().to_string())
+    }
+}
+
+/// The fields attached to a report, as a table with aligned keys
+pub(crate) struct FieldsSection<'a>(pub(crate) Vec<(&'a str, &'a FieldValue)>);
+
+impl Display for FieldsSection<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self.0.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+
+        for (n, (key, value)) in self.0.iter().enumerate() {
+            if n > 0 {
+                writeln!(f)?;
+            }
+
+            write!(f, "{:>width$}: {}", key, value, width = width)?;
+        }
+
+        Ok(())
+    }
+}
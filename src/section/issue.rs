@@ -0,0 +1,997 @@
+#[cfg(feature = "capture-spantrace")]
+use crate::config::{FilteredSpanTrace, SpanFilter, Theme};
+use crate::{
+    fingerprint::Fingerprint,
+    issue::{
+        GitHub, IssueAction, IssueBundle, IssueDecision, IssuePrompt, IssueTemplate, IssueTracker,
+        Markup, NewIssue,
+    },
+    redact::{strip_ansi, Redactor},
+    section::help::HelpInfo,
+    writers::DisplayExt,
+};
+use backtrace::Backtrace;
+use std::{
+    fmt,
+    io::{self, BufRead, Read, Seek, SeekFrom, Write},
+    panic::Location,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+#[cfg(feature = "capture-spantrace")]
+use tracing_error::SpanTrace;
+
+type Display<'a> = Box<dyn std::fmt::Display + Send + Sync + 'a>;
+
+#[derive(Clone)]
+pub(crate) struct IssueSection<'a> {
+    url: &'a str,
+    tracker: &'a dyn IssueTracker,
+    msg: &'a str,
+    message: Option<&'a str>,
+    template: Option<&'a IssueTemplate>,
+    location: Option<&'a Location<'a>>,
+    backtrace: Option<&'a Backtrace>,
+    #[cfg(feature = "capture-spantrace")]
+    span_trace: Option<&'a SpanTrace>,
+    #[cfg(feature = "capture-spantrace")]
+    span_filter: Option<&'a SpanFilter>,
+    metadata: &'a [(String, Display<'a>)],
+    sections: &'a [HelpInfo],
+    redactor: Option<&'a Redactor>,
+    max_len: usize,
+    fingerprint: Option<Fingerprint>,
+    search: bool,
+    labels: Vec<String>,
+    extra_metadata: Vec<(String, String)>,
+}
+
+impl<'a> IssueSection<'a> {
+    pub(crate) fn new(url: &'a str, msg: &'a str) -> Self {
+        IssueSection {
+            url,
+            tracker: &GitHub,
+            msg,
+            message: None,
+            template: None,
+            location: None,
+            backtrace: None,
+            #[cfg(feature = "capture-spantrace")]
+            span_trace: None,
+            #[cfg(feature = "capture-spantrace")]
+            span_filter: None,
+            metadata: &[],
+            sections: &[],
+            redactor: None,
+            max_len: usize::MAX,
+            fingerprint: None,
+            search: false,
+            labels: Vec::new(),
+            extra_metadata: Vec::new(),
+        }
+    }
+
+    pub(crate) fn with_tracker(mut self, tracker: &'a dyn IssueTracker) -> Self {
+        self.tracker = tracker;
+        self
+    }
+
+    /// The error message or panic payload used for the `{message}`
+    /// placeholder, if it differs from the message in the report
+    pub(crate) fn with_message(mut self, message: &'a str) -> Self {
+        self.message = Some(message);
+        self
+    }
+
+    pub(crate) fn with_template(mut self, template: &'a IssueTemplate) -> Self {
+        self.template = Some(template);
+        self
+    }
+
+    pub(crate) fn with_location(mut self, location: impl Into<Option<&'a Location<'a>>>) -> Self {
+        self.location = location.into();
+        self
+    }
+
+    pub(crate) fn with_backtrace(mut self, backtrace: impl Into<Option<&'a Backtrace>>) -> Self {
+        self.backtrace = backtrace.into();
+        self
+    }
+
+    #[cfg(feature = "capture-spantrace")]
+    pub(crate) fn with_span_trace(mut self, span_trace: impl Into<Option<&'a SpanTrace>>) -> Self {
+        self.span_trace = span_trace.into();
+        self
+    }
+
+    #[cfg(feature = "capture-spantrace")]
+    pub(crate) fn with_span_filter(mut self, span_filter: &'a SpanFilter) -> Self {
+        self.span_filter = Some(span_filter);
+        self
+    }
+
+    pub(crate) fn with_metadata(mut self, metadata: &'a [(String, Display<'a>)]) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Include the sections attached to a report, such as notes and
+    /// additional errors
+    pub(crate) fn with_sections(mut self, sections: &'a [HelpInfo]) -> Self {
+        self.sections = sections;
+        self
+    }
+
+    pub(crate) fn with_redactor(mut self, redactor: &'a Redactor) -> Self {
+        self.redactor = Some(redactor);
+        self
+    }
+
+    pub(crate) fn with_max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self
+    }
+
+    pub(crate) fn with_fingerprint(mut self, fingerprint: Fingerprint) -> Self {
+        self.fingerprint = Some(fingerprint);
+        self
+    }
+
+    /// Apply the decision of the issue filter: link to a search for existing
+    /// issues with the same fingerprint instead of a new issue if requested
+    /// and supported by the tracker, and add its labels and metadata
+    pub(crate) fn with_decision(mut self, decision: IssueDecision) -> Self {
+        self.search = decision.action == IssueAction::Search;
+        self.labels = decision.labels;
+        self.extra_metadata = decision.metadata;
+        self
+    }
+}
+
+/// How much of the report to leave out of the issue body to keep the url
+/// within its length limit
+#[derive(Default)]
+struct Elision {
+    frames: Option<usize>,
+    collapse_spans: bool,
+    truncate: bool,
+}
+
+impl IssueSection<'_> {
+    fn body(&self, elision: &Elision) -> Result<Body, fmt::Error> {
+        let markup = self.tracker.markup();
+
+        // The report is redacted after rendering too, but secrets would no
+        // longer be recognizable once the body is url encoded.
+        let redacted = |mut section: String| {
+            if let Some(redactor) = self.redactor {
+                redactor.redact(&mut section);
+            }
+            section
+        };
+
+        let mut error = self.msg.to_string();
+        for section in self
+            .sections
+            .iter()
+            .filter(|s| matches!(s, HelpInfo::Error(..)))
+        {
+            error.push_str("\n\n");
+            error.push_str(&strip_ansi(&section.to_string()));
+        }
+        let error = redacted(ConsoleSection(error, markup).to_string());
+
+        // In the order they are printed in the report.
+        let sections: Vec<_> = self
+            .sections
+            .iter()
+            .filter(|s| matches!(s, HelpInfo::Custom(_)))
+            .chain(self.sections.iter().filter(|s| {
+                !matches!(
+                    s,
+                    HelpInfo::Custom(_) | HelpInfo::Error(..) | HelpInfo::Field(..)
+                )
+            }))
+            .map(|section| strip_ansi(&section.to_string()))
+            .collect();
+        let sections = if sections.is_empty() {
+            None
+        } else {
+            Some(redacted(
+                ConsoleSection(sections.join("\n"), markup).to_string(),
+            ))
+        };
+        let location = self
+            .location
+            .map(|loc| ("location".to_string(), Box::new(loc) as _));
+        let fingerprint = self
+            .fingerprint
+            .map(|fp| ("fingerprint".to_string(), Box::new(fp) as _));
+        let extra_metadata: Vec<(String, Display<'_>)> = self
+            .sections
+            .iter()
+            .filter_map(|section| match section {
+                HelpInfo::Field(key, value) => Some((key.clone(), Box::new(value) as _)),
+                _ => None,
+            })
+            .chain(
+                self.extra_metadata
+                    .iter()
+                    .map(|(key, value)| (key.clone(), Box::new(value) as _)),
+            )
+            .collect();
+        let metadata = self
+            .metadata
+            .iter()
+            .chain(&extra_metadata)
+            .chain(location.as_ref())
+            .chain(fingerprint.as_ref());
+        let metadata = redacted(MetadataSection { metadata, markup }.to_string());
+
+        #[cfg(feature = "capture-spantrace")]
+        let span_trace = self.span_trace.map(|st| {
+            let default_filter = SpanFilter::default();
+            let filter = self.span_filter.unwrap_or(&default_filter);
+
+            let span_trace = if elision.collapse_spans {
+                ConsoleSection(
+                    CollapsedSpanTrace(st, filter).with_header("SpanTrace:\n"),
+                    markup,
+                )
+                .to_string()
+            } else if !filter.is_empty() {
+                Collapsed(
+                    ConsoleSection(
+                        FilteredSpanTrace(st, filter, Theme::new()).with_header("SpanTrace:\n"),
+                        markup,
+                    ),
+                    markup,
+                )
+                .to_string()
+            } else {
+                Collapsed(
+                    ConsoleSection(st.with_header("SpanTrace:\n"), markup),
+                    markup,
+                )
+                .to_string()
+            };
+
+            redacted(span_trace)
+        });
+        #[cfg(not(feature = "capture-spantrace"))]
+        let span_trace: Option<String> = None;
+
+        let backtrace = match (self.backtrace, elision.frames) {
+            (Some(_), Some(0)) | (None, _) => None,
+            (Some(bt), frames) => {
+                let mut bt = format!("{:?}", bt);
+                if let Some(frames) = frames {
+                    truncate_frames(&mut bt, frames);
+                }
+
+                Some(redacted(
+                    Collapsed(
+                        ConsoleSection(bt.with_header("Backtrace:\n"), markup),
+                        markup,
+                    )
+                    .to_string(),
+                ))
+            }
+        };
+
+        let mut body = Body::new(markup);
+
+        let template = match self.template.and_then(|t| t.body.as_deref()) {
+            Some(template) => template,
+            None => {
+                body.push_section("Error", error)?;
+
+                if !self.metadata.is_empty()
+                    || !extra_metadata.is_empty()
+                    || self.fingerprint.is_some()
+                {
+                    body.push_section("Metadata", metadata)?;
+                }
+
+                body.required = body.body.len();
+
+                if let Some(sections) = sections {
+                    body.push_section("Sections", sections)?;
+                }
+
+                if let Some(span_trace) = span_trace {
+                    body.push_section("SpanTrace", span_trace)?;
+                }
+
+                if let Some(backtrace) = backtrace {
+                    body.push_section("Backtrace", backtrace)?;
+                }
+
+                return Ok(body);
+            }
+        };
+
+        let (filled, required) = fill(template, |name| match name {
+            "error" => Some((error.clone(), true)),
+            "metadata" => Some((metadata.clone(), true)),
+            "sections" => Some((sections.clone().unwrap_or_default(), false)),
+            "span_trace" => Some((span_trace.clone().unwrap_or_default(), false)),
+            "backtrace" => Some((backtrace.clone().unwrap_or_default(), false)),
+            name => self
+                .placeholder(name)
+                .map(|value| (redacted(value.unwrap_or_default()), true)),
+        });
+
+        body.body = filled;
+        body.required = required;
+
+        Ok(body)
+    }
+
+    /// The value of a placeholder that can be used in both the title and the
+    /// body, or `None` if there is no placeholder with this name
+    fn placeholder(&self, name: &str) -> Option<Option<String>> {
+        match name {
+            "message" => Some(
+                self.message
+                    .unwrap_or(self.msg)
+                    .lines()
+                    .next()
+                    .map(str::to_string),
+            ),
+            "location" => Some(self.location.map(ToString::to_string)),
+            "fingerprint" => Some(self.fingerprint.map(|fp| fp.to_string())),
+            "version" => Some(
+                self.metadata
+                    .iter()
+                    .find(|(key, _)| key == "version")
+                    .map(|(_, value)| value.to_string()),
+            ),
+            _ => None,
+        }
+    }
+
+    fn title(&self) -> String {
+        let template = match self.template {
+            Some(template) => &template.title,
+            None => return "<autogenerated-issue>".to_string(),
+        };
+
+        let (mut title, _) = fill(template, |name| {
+            self.placeholder(name)
+                .map(|value| (value.unwrap_or_default(), false))
+        });
+
+        if let Some(redactor) = self.redactor {
+            redactor.redact(&mut title);
+        }
+
+        title
+    }
+
+    fn url(&self, title: &str, body: &str) -> Option<String> {
+        let (labels, assignees, template): (&[String], &[String], _) = match self.template {
+            Some(template) => (
+                &template.labels,
+                &template.assignees,
+                template.template.as_deref(),
+            ),
+            None => (&[], &[], None),
+        };
+
+        let labels: Vec<_> = labels.iter().chain(&self.labels).cloned().collect();
+
+        self.tracker.new_issue_url(
+            self.url,
+            &NewIssue {
+                title,
+                body,
+                labels: &labels,
+                assignees,
+                template,
+            },
+        )
+    }
+
+    /// Build the issue, leaving out more and more of the report until its url
+    /// fits within the length limit
+    fn fitted_issue(&self) -> Result<FittedIssue, fmt::Error> {
+        let fits = |url: &Option<String>| !matches!(url, Some(url) if url.len() > self.max_len);
+
+        let title = self.title();
+        let issue = |body: String| FittedIssue {
+            url: self.url(&title, &body),
+            title: title.clone(),
+            body,
+        };
+        let mut steps = vec![Elision::default()];
+
+        if self.backtrace.is_some() {
+            steps.extend(FRAME_BUDGETS.iter().map(|&frames| Elision {
+                frames: Some(frames),
+                ..Elision::default()
+            }));
+        }
+
+        #[cfg(feature = "capture-spantrace")]
+        if matches!(self.span_trace, Some(st) if st.status() == tracing_error::SpanTraceStatus::CAPTURED)
+        {
+            steps.push(Elision {
+                frames: self.backtrace.map(|_| 0),
+                collapse_spans: true,
+                truncate: false,
+            });
+        }
+
+        let mut last = None;
+        for elision in steps {
+            let body = self.body(&elision)?;
+            let fitted = issue(body.elided(&elision));
+            if fits(&fitted.url) {
+                return Ok(fitted);
+            }
+
+            last = Some((body, elision));
+        }
+
+        // Truncate the rest of the report, always keeping the error and the
+        // metadata.
+        let (body, mut elision) = last.expect("there is always at least one step");
+        elision.truncate = true;
+
+        let mut lo = body.required;
+        let mut hi = body.body.len();
+        while lo < hi {
+            let mid = floor_char_boundary(&body.body, lo + (hi - lo).div_ceil(2));
+            if mid <= lo {
+                break;
+            }
+
+            if fits(&self.url(&title, &body.truncated(mid).elided(&elision))) {
+                lo = mid;
+            } else {
+                hi = mid - 1;
+            }
+        }
+
+        Ok(issue(body.truncated(lo).elided(&elision)))
+    }
+
+    /// The url to search for existing issues with the same fingerprint, if
+    /// searching was requested by the issue filter
+    fn search_url(&self) -> Option<String> {
+        match self.fingerprint {
+            Some(fp) if self.search => self.tracker.search_url(self.url, &fp.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Preview the issue and ask whether to open it, if stdin and stderr are
+    /// terminals
+    pub(crate) fn prompt(&self, prompt: &IssuePrompt) -> io::Result<()> {
+        use std::io::IsTerminal;
+
+        if !(io::stdin().is_terminal() && io::stderr().is_terminal()) {
+            return Ok(());
+        }
+
+        self.prompt_with(prompt, &mut io::stdin().lock(), &mut io::stderr())
+    }
+
+    /// Preview the issue and ask whether to open it, reading the answer from
+    /// `input`
+    pub(crate) fn prompt_with(
+        &self,
+        prompt: &IssuePrompt,
+        input: &mut impl BufRead,
+        output: &mut impl Write,
+    ) -> io::Result<()> {
+        let (url, question) = match self.search_url() {
+            Some(url) => (url, "Search for existing reports of this error?"),
+            None => {
+                let issue = self.fitted_issue().map_err(|_| io::ErrorKind::Other)?;
+
+                let url = match issue.url {
+                    Some(url) => url,
+                    None => return Ok(()),
+                };
+
+                writeln!(output, "\nThis is the issue that would be submitted:\n")?;
+                writeln!(output, "Title: {}\n\n{}\n", issue.title, issue.body)?;
+
+                (url, "Open this issue in the browser?")
+            }
+        };
+
+        write!(
+            output,
+            "{} [y]es, [s]ave the url to a file, [N]o: ",
+            question
+        )?;
+        output.flush()?;
+
+        let mut answer = String::new();
+        input.read_line(&mut answer)?;
+
+        match answer.trim().to_lowercase().as_str() {
+            "y" | "yes" => match (prompt.opener)(&url) {
+                Ok(()) => Ok(()),
+                Err(error) => {
+                    writeln!(output, "Failed to open the browser: {}", error)?;
+                    self.save_url(prompt, &url, output)
+                }
+            },
+            "s" | "save" => self.save_url(prompt, &url, output),
+            _ => Ok(()),
+        }
+    }
+
+    fn save_url(&self, prompt: &IssuePrompt, url: &str, output: &mut impl Write) -> io::Result<()> {
+        let name = match self.fingerprint {
+            Some(fp) => format!("issue-url-{}.txt", fp),
+            None => "issue-url.txt".to_string(),
+        };
+        let path = prompt.save_dir.join(name);
+
+        std::fs::create_dir_all(&prompt.save_dir)?;
+        std::fs::write(&path, format!("{}\n", url))?;
+
+        writeln!(output, "Saved the issue url to: {}", path.display())
+    }
+}
+
+/// An issue with the parts of the report that fit into its url
+struct FittedIssue {
+    title: String,
+    body: String,
+    url: Option<String>,
+}
+
+/// The number of backtrace frames tried, in order, when the issue url is too
+/// long
+const FRAME_BUDGETS: [usize; 5] = [32, 16, 8, 4, 0];
+
+impl fmt::Display for IssueSection<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(url) = self.search_url() {
+            return url
+                .with_header("This error may have been reported already, see: ")
+                .fmt(f);
+        }
+
+        let url = self.fitted_issue()?.url;
+
+        let url: &dyn fmt::Display = match &url {
+            Some(url) => url,
+            None => &self.url,
+        };
+
+        url.with_header("Consider reporting this error using this URL: ")
+            .fmt(f)
+    }
+}
+
+/// Replace the `{name}` placeholders in `template` with the values returned
+/// by `value`, which also tells whether the value must be kept when the body
+/// is truncated. Returns the filled template and the length of its required
+/// part
+fn fill(template: &str, value: impl Fn(&str) -> Option<(String, bool)>) -> (String, usize) {
+    let mut out = String::new();
+    let mut required = 0;
+    let mut rest = template;
+
+    while let Some(i) = rest.find(&['{', '}'][..]) {
+        out.push_str(&rest[..i]);
+        rest = &rest[i..];
+
+        if rest.starts_with("{{") || rest.starts_with("}}") {
+            out.push_str(&rest[..1]);
+            rest = &rest[2..];
+            continue;
+        }
+
+        let placeholder = rest
+            .find('}')
+            .filter(|_| rest.starts_with('{'))
+            .and_then(|end| Some((value(&rest[1..end])?, end)));
+
+        match placeholder {
+            Some(((value, is_required), end)) => {
+                out.push_str(&value);
+                if is_required {
+                    required = out.len();
+                }
+                rest = &rest[end + 1..];
+            }
+            // Unknown placeholders are kept as they are.
+            None => {
+                out.push_str(&rest[..1]);
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    out.push_str(rest);
+    (out, required)
+}
+
+/// Save `issue` as an offline bug report bundle in the bundle directory, and
+/// return the path of the new file
+pub(crate) fn save_bundle(
+    mut issue: IssueSection<'_>,
+    bundle: &IssueBundle,
+) -> io::Result<PathBuf> {
+    // Bundles are always markdown files with the default layout.
+    issue.tracker = &GitHub;
+    issue.template = None;
+
+    let contents = bundle_contents(&issue, bundle).map_err(|_| io::ErrorKind::Other)?;
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs());
+    let name = match issue.fingerprint {
+        Some(fingerprint) => format!("bug-report-{}-{}.md", fingerprint, timestamp),
+        None => format!("bug-report-{}.md", timestamp),
+    };
+    let path = bundle.dir.join(name);
+
+    std::fs::create_dir_all(&bundle.dir)?;
+    std::fs::write(&path, contents)?;
+
+    Ok(path)
+}
+
+fn bundle_contents(issue: &IssueSection<'_>, bundle: &IssueBundle) -> Result<String, fmt::Error> {
+    use std::fmt::Write;
+
+    let mut body = issue.body(&Elision::default())?;
+    let required = body.body.len();
+
+    body.push_section("Environment", EnvironmentSection)?;
+
+    if let Some((path, lines)) = &bundle.log {
+        let tail = match log_tail(path, *lines) {
+            Ok(tail) => tail,
+            Err(error) => format!("failed to read {}: {}", path.display(), error),
+        };
+
+        body.push_section("Log", ConsoleSection(tail.trim_end(), Markup::Markdown))?;
+    }
+
+    // The sections from the issue body are already redacted.
+    if let Some(redactor) = issue.redactor {
+        let mut rest = body.body.split_off(required);
+        redactor.redact(&mut rest);
+        body.body.push_str(&rest);
+    }
+
+    let mut contents = String::from("# Bug report\n\n");
+    writeln!(&mut contents, "{}", body.body)?;
+
+    Ok(contents)
+}
+
+/// Prints where a bug report bundle was saved, or why it couldn't be
+pub(crate) struct BundleSection<'a> {
+    pub(crate) saved: &'a io::Result<PathBuf>,
+    pub(crate) bundle: &'a IssueBundle,
+}
+
+impl fmt::Display for BundleSection<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.saved {
+            Ok(path) => write!(
+                f,
+                "A bug report was saved to: {}\n{}",
+                path.display(),
+                self.bundle.instructions
+            ),
+            Err(error) => write!(
+                f,
+                "Failed to save a bug report to {}: {}",
+                self.bundle.dir.display(),
+                error
+            ),
+        }
+    }
+}
+
+/// The maximum number of bytes read from the end of a log file
+const LOG_TAIL_MAX_BYTES: u64 = 256 * 1024;
+
+/// The last `lines` lines of the file at `path`
+fn log_tail(path: &Path, lines: usize) -> io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    let start = len.saturating_sub(LOG_TAIL_MAX_BYTES);
+    file.seek(SeekFrom::Start(start))?;
+
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+    let text = String::from_utf8_lossy(&bytes);
+
+    let all: Vec<_> = text.lines().collect();
+    // The first line may have been cut by the seek.
+    let available = if start > 0 {
+        &all[1.min(all.len())..]
+    } else {
+        &all[..]
+    };
+    let tail = &available[available.len().saturating_sub(lines)..];
+
+    Ok(tail.join("\n"))
+}
+
+/// The environment variables included in bundles, all others are left out
+const BUNDLE_ENV_VARS: &[&str] = &["RUST_BACKTRACE", "RUST_LIB_BACKTRACE", "RUST_SPANTRACE"];
+
+/// Information about the toolchain, system and process a bundle was saved on
+struct EnvironmentSection;
+
+impl fmt::Display for EnvironmentSection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "|key|value|")?;
+        writeln!(f, "|--|--|")?;
+        writeln!(f, "|**rustc**|{}|", env!("COLOR_EYRE_RUSTC_VERSION"))?;
+        writeln!(f, "|**target**|{}|", env!("COLOR_EYRE_TARGET"))?;
+        writeln!(f, "|**os**|{}|", crate::environment::os())?;
+        writeln!(f, "|**arch**|{}|", std::env::consts::ARCH)?;
+
+        if let Ok(exe) = std::env::current_exe() {
+            writeln!(f, "|**executable**|{}|", exe.display())?;
+        }
+
+        if let Ok(time) = SystemTime::now().duration_since(UNIX_EPOCH) {
+            writeln!(f, "|**unix time**|{}|", time.as_secs())?;
+        }
+
+        for var in BUNDLE_ENV_VARS {
+            if let Ok(value) = std::env::var(var) {
+                writeln!(f, "|**{}**|{}|", var, value)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Cut a `Debug` formatted backtrace after its first `frames` frames
+fn truncate_frames(bt: &mut String, frames: usize) {
+    let mut offset = 0;
+    let mut seen = 0;
+
+    for line in bt.split_inclusive('\n') {
+        if is_frame_line(line) {
+            if seen == frames {
+                let hidden = bt[offset..]
+                    .lines()
+                    .filter(|line| is_frame_line(line))
+                    .count();
+                bt.truncate(offset);
+                bt.push_str(&format!("      ⋮ {} more frames", hidden));
+                return;
+            }
+
+            seen += 1;
+        }
+
+        offset += line.len();
+    }
+}
+
+/// Whether `line` starts a frame of a `Debug` formatted backtrace, e.g.
+/// `   3: main`
+fn is_frame_line(line: &str) -> bool {
+    matches!(
+        line.trim_start().split_once(':'),
+        Some((n, _)) if !n.is_empty() && n.chars().all(|c| c.is_ascii_digit())
+    )
+}
+
+fn floor_char_boundary(s: &str, mut i: usize) -> usize {
+    while !s.is_char_boundary(i) {
+        i -= 1;
+    }
+    i
+}
+
+/// Only the names of the spans in a `SpanTrace`
+#[cfg(feature = "capture-spantrace")]
+struct CollapsedSpanTrace<'a>(&'a SpanTrace, &'a SpanFilter);
+
+#[cfg(feature = "capture-spantrace")]
+impl fmt::Display for CollapsedSpanTrace<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (spans, _) = self.1.collect(self.0);
+
+        for (n, span) in spans.iter().enumerate() {
+            if n > 0 {
+                writeln!(f)?;
+            }
+
+            write!(f, "{:>4}: {}::{}", n, span.target, span.name)?;
+        }
+
+        Ok(())
+    }
+}
+
+struct Body {
+    body: String,
+    markup: Markup,
+    /// The length of the part of the body that is never elided
+    required: usize,
+}
+
+impl Body {
+    fn new(markup: Markup) -> Self {
+        Body {
+            body: String::new(),
+            markup,
+            required: 0,
+        }
+    }
+
+    /// The body cut at `len`, with any code or collapsed blocks closed
+    fn truncated(&self, len: usize) -> Body {
+        let mut body = self.body[..len].to_string();
+
+        body.push_str("\n…");
+        match self.markup {
+            Markup::Markdown => {
+                if body.matches("```").count() % 2 == 1 {
+                    body.push_str("\n```");
+                }
+
+                if body.matches("<details>").count() > body.matches("</details>").count() {
+                    body.push_str("\n</details>");
+                }
+            }
+            Markup::JiraWiki => {
+                if body.matches("{noformat}").count() % 2 == 1 {
+                    body.push_str("\n{noformat}");
+                }
+            }
+        }
+
+        Body {
+            body,
+            markup: self.markup,
+            required: self.required,
+        }
+    }
+
+    /// The body with a note on what was left out of it
+    fn elided(&self, elision: &Elision) -> String {
+        let mut elided = Vec::new();
+
+        match elision.frames {
+            Some(0) => elided.push("the backtrace was left out".to_string()),
+            Some(frames) => elided.push(format!(
+                "the backtrace was shortened to its first {} frames",
+                frames
+            )),
+            None => (),
+        }
+
+        if elision.collapse_spans {
+            elided.push("the span trace was collapsed to span names".to_string());
+        }
+
+        if elision.truncate {
+            elided.push("the report was truncated".to_string());
+        }
+
+        if elided.is_empty() {
+            return self.body.clone();
+        }
+
+        let note = format!(
+            "Some information was left out to keep the url of this issue short enough to open: {}.",
+            elided.join(", ")
+        );
+
+        let (prefix, note) = match self.markup {
+            Markup::Markdown => ("## ", format!("_{}_", note)),
+            Markup::JiraWiki => ("h2. ", format!("_{}_", note)),
+        };
+
+        format!("{}\n\n{}Note\n{}", self.body, prefix, note)
+    }
+    fn push_section<T>(&mut self, header: &'static str, section: T) -> fmt::Result
+    where
+        T: fmt::Display,
+    {
+        use std::fmt::Write;
+
+        let separator = if self.body.is_empty() { "" } else { "\n\n" };
+        let prefix = match self.markup {
+            Markup::Markdown => "## ",
+            Markup::JiraWiki => "h2. ",
+        };
+        let header = header
+            .with_header(prefix)
+            .with_header(separator)
+            .with_footer("\n");
+
+        write!(&mut self.body, "{}", section.with_header(header))
+    }
+}
+
+struct MetadataSection<T> {
+    metadata: T,
+    markup: Markup,
+}
+
+impl<'a, T> MetadataSection<T>
+where
+    T: IntoIterator<Item = &'a (String, Display<'a>)>,
+{
+    // This is implemented as a free functions so it can consume the `metadata`
+    // iterator, rather than being forced to leave it unmodified if its behind a
+    // `&self` shared reference via the Display trait
+    #[allow(clippy::inherent_to_string, clippy::wrong_self_convention)]
+    fn to_string(self) -> String {
+        use std::fmt::Write;
+
+        let mut out = String::new();
+        let f = &mut out;
+
+        match self.markup {
+            Markup::Markdown => {
+                writeln!(f, "|key|value|").expect("writing to a string doesn't panic");
+                writeln!(f, "|--|--|").expect("writing to a string doesn't panic");
+
+                for (key, value) in self.metadata {
+                    writeln!(f, "|**{}**|{}|", key, value)
+                        .expect("writing to a string doesn't panic");
+                }
+            }
+            Markup::JiraWiki => {
+                writeln!(f, "||key||value||").expect("writing to a string doesn't panic");
+
+                for (key, value) in self.metadata {
+                    writeln!(f, "|*{}*|{}|", key, value)
+                        .expect("writing to a string doesn't panic");
+                }
+            }
+        }
+
+        out
+    }
+}
+
+struct ConsoleSection<T>(T, Markup);
+
+impl<T> fmt::Display for ConsoleSection<T>
+where
+    T: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.1 {
+            Markup::Markdown => (&self.0).with_header("```\n").with_footer("\n```").fmt(f),
+            Markup::JiraWiki => (&self.0)
+                .with_header("{noformat}\n")
+                .with_footer("\n{noformat}")
+                .fmt(f),
+        }
+    }
+}
+
+struct Collapsed<T>(T, Markup);
+
+impl<T> fmt::Display for Collapsed<T>
+where
+    T: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.1 {
+            Markup::Markdown => (&self.0)
+                .with_header("\n<details>\n\n")
+                .with_footer("\n</details>")
+                .fmt(f),
+            // Jira has no collapsible blocks that work in descriptions.
+            Markup::JiraWiki => self.0.fmt(f),
+        }
+    }
+}
@@ -2,9 +2,13 @@
 use crate::writers::WriterExt;
 use std::fmt::{self, Display};
 
-#[cfg(feature = "issue-url")]
-pub(crate) mod github;
+mod collect;
 pub(crate) mod help;
+#[cfg(feature = "issue-url")]
+pub(crate) mod issue;
+
+pub use collect::{Aggregate, CollectErrors};
+pub use help::{DefinedKind, FieldValue, SectionKind, SectionRef};
 
 /// An indented section with a header for an error report
 ///
@@ -230,6 +234,88 @@ pub trait Section: crate::private::Sealed {
         F: FnOnce() -> E,
         E: std::error::Error + Send + Sync + 'static;
 
+    /// Add a key/value field to an error report, to be displayed with the
+    /// other fields in a table after the chain of errors.
+    ///
+    /// # Details
+    ///
+    /// Fields keep the type of their value, so they are written as numbers,
+    /// booleans or strings in crash reports, and are added to the metadata
+    /// table of generated issues.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,should_panic
+    /// use color_eyre::{eyre::eyre, eyre::Report, Section};
+    ///
+    /// Err(eyre!("request failed"))
+    ///     .field("request_id", "4f2a9c")
+    ///     .field("path", "/api/users")
+    ///     .field("retry", 3)?;
+    /// # Ok::<_, Report>(())
+    /// ```
+    fn field<K, V>(self, key: K, value: V) -> Self::Return
+    where
+        K: Into<String>,
+        V: Into<FieldValue>;
+
+    /// Add a key/value field to an error report, to be displayed with the
+    /// other fields in a table after the chain of errors. The closure to
+    /// create the value is lazily evaluated only in the case of an error.
+    fn with_field<K, V, F>(self, key: K, value: F) -> Self::Return
+    where
+        K: Into<String>,
+        V: Into<FieldValue>,
+        F: FnOnce() -> V;
+
+    /// Set the error code of an error report, printed above the chain of
+    /// errors with its documentation link.
+    ///
+    /// # Details
+    ///
+    /// The link is built from the template set with
+    /// `HookBuilder::error_docs_url`. The code is also written to crash
+    /// reports and the metadata of generated issues, and can be read back
+    /// with `Handler::code`, e.g. to choose an exit code. Setting a code
+    /// again replaces the previous one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,should_panic
+    /// use color_eyre::{eyre::eyre, eyre::Report, Section};
+    ///
+    /// Err(eyre!("the config file is missing")).code("E0042")?;
+    /// # Ok::<_, Report>(())
+    /// ```
+    fn code<C>(self, code: C) -> Self::Return
+    where
+        C: Into<String>;
+
+    /// Add a section of a user-defined kind to an error report, printed with
+    /// the label, style and icon of the kind.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,should_panic
+    /// use color_eyre::{eyre::eyre, eyre::Report, section::DefinedKind, Section};
+    ///
+    /// let workaround = DefinedKind::new("Workaround").priority(55);
+    ///
+    /// Err(eyre!("the cache is corrupted"))
+    ///     .labeled(workaround, "delete ~/.cache/app and try again")?;
+    /// # Ok::<_, Report>(())
+    /// ```
+    fn labeled<D>(self, kind: DefinedKind, body: D) -> Self::Return
+    where
+        D: Display + Send + Sync + 'static;
+
+    /// Add a section of a user-defined kind to an error report. The closure
+    /// to create the body is lazily evaluated only in the case of an error.
+    fn with_labeled<D, F>(self, kind: DefinedKind, body: F) -> Self::Return
+    where
+        D: Display + Send + Sync + 'static,
+        F: FnOnce() -> D;
+
     /// Add a Note to an error report, to be displayed after the chain of errors.
     ///
     /// # Examples
@@ -0,0 +1,598 @@
+//! Offline symbolization of backtraces captured in binaries without symbols
+//!
+//! # Details
+//!
+//! With the `raw-backtrace` feature enabled, when `color-eyre` can't resolve
+//! the symbols of the executable, for example because it was stripped, it
+//! prints a compact raw backtrace instead of the usual styled one. The raw
+//! backtrace lists every loaded module that appears in the backtrace by its
+//! build-id, and every frame as an offset into one of those modules.
+//!
+//! With the `symbolize` feature enabled, a [`Symbolizer`] loaded with the
+//! unstripped binary or its split debuginfo turns a raw backtrace back into
+//! the regular colored backtrace output. See [`examples/symbolize.rs`] for a
+//! small command line tool built on top of it.
+//!
+//! [`Symbolizer`]: struct.Symbolizer.html
+//! [`examples/symbolize.rs`]: https://github.com/yaahc/color-eyre/blob/master/examples/symbolize.rs
+use crate::config::Frame;
+use std::{fmt, path::PathBuf, str::FromStr};
+
+const MARKER: &str = "color-eyre raw backtrace v";
+const VERSION: &str = "1";
+
+/// A backtrace whose frames are recorded as offsets into the modules they
+/// were loaded from
+///
+/// # Details
+///
+/// The `Display` impl produces the format embedded in error reports, and the
+/// `FromStr` impl parses it back. When parsing, any text before the raw
+/// backtrace is skipped, so an entire error report can be parsed directly.
+///
+/// Frame offsets are relative to the address each module's first segment was
+/// mapped at, which works for position independent and fixed address
+/// executables alike.
+///
+/// # Examples
+///
+/// ```rust
+/// use color_eyre::symbolize::RawBacktrace;
+///
+/// let report = "\
+/// Error:
+///    0: something went wrong
+///
+///   color-eyre raw backtrace v1
+///   m0 8f3c1a2b /usr/bin/my-app
+///   0 m0+0x1a2b3c
+///   1 m0+0x1a0f00
+///   2 0x7f3e9c1d2e3f __libc_start_main
+/// ";
+///
+/// let raw: RawBacktrace = report.parse().unwrap();
+/// assert_eq!(raw.len(), 3);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RawBacktrace {
+    modules: Vec<RawModule>,
+    frames: Vec<RawFrame>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RawModule {
+    build_id: Option<Vec<u8>>,
+    path: PathBuf,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RawFrame {
+    /// index into `modules`, `address` is absolute if this is `None`
+    module: Option<usize>,
+    address: usize,
+    name: Option<String>,
+}
+
+impl RawBacktrace {
+    /// Record the physical frames of a resolved backtrace.
+    pub(crate) fn from_frames(frames: &[Frame]) -> Self {
+        let mut raw = RawBacktrace::default();
+
+        // Inlined symbols follow their physical frame, which is the only one
+        // with an address of its own.
+        for frame in frames.iter().filter(|frame| !frame.inlined) {
+            let ip = match frame.ip {
+                Some(ip) => ip,
+                None => continue,
+            };
+
+            let module = match (frame.module.as_ref(), frame.module_base) {
+                (Some(path), Some(base)) => Some((path, base)),
+                _ => None,
+            };
+
+            let (module, address) = match module {
+                Some((path, base)) => {
+                    let index = match raw.modules.iter().position(|m| &m.path == path) {
+                        Some(index) => index,
+                        None => {
+                            raw.modules.push(RawModule {
+                                build_id: build_id(path),
+                                path: path.clone(),
+                            });
+                            raw.modules.len() - 1
+                        }
+                    };
+
+                    (Some(index), ip.wrapping_sub(base))
+                }
+                None => (None, ip),
+            };
+
+            raw.frames.push(RawFrame {
+                module,
+                address,
+                name: frame.name.clone(),
+            });
+        }
+
+        raw
+    }
+
+    /// Returns the number of frames in the backtrace
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns `true` if the backtrace contains no frames
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+}
+
+/// Read the build-id (or Mach-O UUID) of the module at `path`
+fn build_id(path: &std::path::Path) -> Option<Vec<u8>> {
+    use object::Object;
+
+    let file = std::fs::File::open(path).ok()?;
+    let cache = object::ReadCache::new(file);
+    let object = object::File::parse(&cache).ok()?;
+
+    if let Ok(Some(id)) = object.build_id() {
+        return Some(id.to_vec());
+    }
+
+    object.mach_uuid().ok().flatten().map(|uuid| uuid.to_vec())
+}
+
+impl fmt::Display for RawBacktrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", MARKER, VERSION)?;
+
+        for (n, module) in self.modules.iter().enumerate() {
+            write!(f, "\nm{} ", n)?;
+            match &module.build_id {
+                Some(id) => id.iter().try_for_each(|byte| write!(f, "{:02x}", byte))?,
+                None => f.write_str("-")?,
+            }
+            write!(f, " {}", module.path.display())?;
+        }
+
+        for (n, frame) in self.frames.iter().enumerate() {
+            match frame.module {
+                Some(module) => write!(f, "\n{} m{}+{:#x}", n, module, frame.address)?,
+                None => write!(f, "\n{} {:#x}", n, frame.address)?,
+            }
+
+            if let Some(name) = &frame.name {
+                write!(f, " {}", name)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The error type returned when parsing a `RawBacktrace` fails
+#[derive(Debug)]
+pub struct ParseRawBacktraceError {
+    kind: ParseErrorKind,
+}
+
+#[derive(Debug)]
+enum ParseErrorKind {
+    Missing,
+    Version(String),
+    Line(String),
+}
+
+impl fmt::Display for ParseRawBacktraceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ParseErrorKind::Missing => write!(
+                f,
+                "no raw backtrace found, expected a {:?} line",
+                format!("{}{}", MARKER, VERSION)
+            ),
+            ParseErrorKind::Version(version) => write!(
+                f,
+                "unsupported raw backtrace version v{}, only v{} is supported",
+                version, VERSION
+            ),
+            ParseErrorKind::Line(line) => write!(f, "invalid raw backtrace line: {:?}", line),
+        }
+    }
+}
+
+impl std::error::Error for ParseRawBacktraceError {}
+
+impl FromStr for RawBacktrace {
+    type Err = ParseRawBacktraceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines().map(str::trim);
+
+        let version =
+            lines
+                .find_map(|line| line.strip_prefix(MARKER))
+                .ok_or(ParseRawBacktraceError {
+                    kind: ParseErrorKind::Missing,
+                })?;
+
+        if version != VERSION {
+            return Err(ParseRawBacktraceError {
+                kind: ParseErrorKind::Version(version.to_string()),
+            });
+        }
+
+        let mut raw = RawBacktrace::default();
+
+        for line in lines {
+            if line.is_empty() {
+                break;
+            }
+
+            let invalid = || ParseRawBacktraceError {
+                kind: ParseErrorKind::Line(line.to_string()),
+            };
+
+            let mut parts = line.splitn(3, ' ');
+            let first = parts.next().ok_or_else(invalid)?;
+
+            if let Some(n) = first.strip_prefix('m') {
+                if n.parse::<usize>().ok() != Some(raw.modules.len()) {
+                    return Err(invalid());
+                }
+
+                let build_id = match parts.next().ok_or_else(invalid)? {
+                    "-" => None,
+                    hex => Some(parse_hex_bytes(hex).ok_or_else(invalid)?),
+                };
+                let path = parts.next().ok_or_else(invalid)?.into();
+
+                raw.modules.push(RawModule { build_id, path });
+            } else {
+                first.parse::<usize>().map_err(|_| invalid())?;
+
+                let location = parts.next().ok_or_else(invalid)?;
+                let (module, address) = match location.split_once('+') {
+                    Some((module, offset)) => {
+                        let module = module
+                            .strip_prefix('m')
+                            .and_then(|n| n.parse::<usize>().ok())
+                            .filter(|n| *n < raw.modules.len())
+                            .ok_or_else(invalid)?;
+
+                        (Some(module), parse_hex(offset).ok_or_else(invalid)?)
+                    }
+                    None => (None, parse_hex(location).ok_or_else(invalid)?),
+                };
+                let name = parts.next().map(str::to_string);
+
+                raw.frames.push(RawFrame {
+                    module,
+                    address,
+                    name,
+                });
+            }
+        }
+
+        Ok(raw)
+    }
+}
+
+fn parse_hex(s: &str) -> Option<usize> {
+    usize::from_str_radix(s.strip_prefix("0x")?, 16).ok()
+}
+
+fn parse_hex_bytes(s: &str) -> Option<Vec<u8>> {
+    s.as_bytes()
+        .chunks(2)
+        .map(|pair| match pair {
+            [_, _] => u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok(),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(feature = "symbolize")]
+pub use symbolizer::{SymbolizedBacktrace, Symbolizer};
+
+#[cfg(feature = "symbolize")]
+mod symbolizer {
+    use super::{RawBacktrace, RawFrame, RawModule};
+    use crate::config::{FilterCallback, Frame, FramesFormatter, Theme, TraceLayout};
+    use object::Object;
+    use std::{fmt, path::PathBuf};
+
+    /// Resolves raw backtraces using unstripped binaries or split debuginfo
+    ///
+    /// # Details
+    ///
+    /// Frames are matched to the objects added with `add_object` by build-id,
+    /// or by file name for modules that didn't have a build-id when the raw
+    /// backtrace was captured. Frames whose module wasn't provided keep the
+    /// symbol name recorded at capture time, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use color_eyre::symbolize::{RawBacktrace, Symbolizer};
+    ///
+    /// let report = std::fs::read_to_string("crash-report.txt")?;
+    /// let raw: RawBacktrace = report.parse()?;
+    ///
+    /// let symbolizer = Symbolizer::new().add_object("target/release/my-app.debug")?;
+    /// println!("{}", symbolizer.symbolize(&raw));
+    /// # Ok::<_, color_eyre::eyre::Report>(())
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "symbolize")))]
+    pub struct Symbolizer {
+        objects: Vec<LoadedObject>,
+        filters: Vec<Box<FilterCallback>>,
+        theme: Theme,
+    }
+
+    struct LoadedObject {
+        path: PathBuf,
+        build_id: Option<Vec<u8>>,
+        data: Vec<u8>,
+    }
+
+    impl Symbolizer {
+        /// Construct a `Symbolizer` with the default frame filters and theme
+        pub fn new() -> Self {
+            Self::blank()
+                .add_frame_filter(Box::new(crate::config::default_frame_filter))
+                .add_frame_filter(Box::new(crate::config::eyre_frame_filters))
+        }
+
+        /// Construct a `Symbolizer` without any frame filters
+        pub fn blank() -> Self {
+            Symbolizer {
+                objects: vec![],
+                filters: vec![],
+                theme: Theme::dark(),
+            }
+        }
+
+        /// Set the theme used to display symbolized backtraces
+        pub fn theme(mut self, theme: Theme) -> Self {
+            self.theme = theme;
+            self
+        }
+
+        /// Add a custom filter to the set of frame filters
+        pub fn add_frame_filter(mut self, filter: Box<FilterCallback>) -> Self {
+            self.filters.push(filter);
+            self
+        }
+
+        /// Load an unstripped binary or split debuginfo file to resolve frames
+        /// from
+        pub fn add_object<P: Into<PathBuf>>(
+            mut self,
+            path: P,
+        ) -> Result<Self, crate::eyre::Report> {
+            use crate::eyre::WrapErr;
+
+            let path = path.into();
+            let data = std::fs::read(&path)
+                .wrap_err_with(|| format!("failed to read {}", path.display()))?;
+            let build_id = {
+                let object = object::File::parse(&*data)
+                    .wrap_err_with(|| format!("failed to parse {}", path.display()))?;
+
+                match object.build_id() {
+                    Ok(Some(id)) => Some(id.to_vec()),
+                    _ => object.mach_uuid().ok().flatten().map(|uuid| uuid.to_vec()),
+                }
+            };
+
+            self.objects.push(LoadedObject {
+                path,
+                build_id,
+                data,
+            });
+
+            Ok(self)
+        }
+
+        /// Resolve the frames of a raw backtrace
+        pub fn symbolize(&self, raw: &RawBacktrace) -> SymbolizedBacktrace<'_> {
+            let objects: Vec<_> = raw
+                .modules
+                .iter()
+                .map(|module| self.find_object(module).and_then(ResolvedObject::load))
+                .collect();
+
+            let mut frames = Vec::new();
+
+            for (i, frame) in raw.frames.iter().enumerate() {
+                let object = frame
+                    .module
+                    .and_then(|module| objects.get(module))
+                    .and_then(Option::as_ref);
+
+                let mut resolved = object
+                    .map(|object| object.resolve(frame))
+                    .unwrap_or_default();
+
+                let module = frame.module.map(|module| raw.modules[module].path.clone());
+                let new_frame = |name, filename, lineno, inlined| Frame {
+                    n: i + 1,
+                    name,
+                    lineno,
+                    filename,
+                    ip: Some(frame.address),
+                    module: module.clone(),
+                    module_base: frame.module.map(|_| 0),
+                    inlined,
+                };
+
+                // The last symbol is the physical frame, and the ones before
+                // it were inlined into it.
+                match resolved.pop() {
+                    None => frames.push(new_frame(frame.name.clone(), None, None, false)),
+                    Some((name, filename, lineno)) => {
+                        frames.push(new_frame(name, filename, lineno, false));
+                        frames.extend(resolved.into_iter().map(|(name, filename, lineno)| {
+                            new_frame(name, filename, lineno, true)
+                        }));
+                    }
+                }
+            }
+
+            SymbolizedBacktrace {
+                symbolizer: self,
+                frames,
+            }
+        }
+
+        fn find_object(&self, module: &RawModule) -> Option<&LoadedObject> {
+            match &module.build_id {
+                Some(id) => self
+                    .objects
+                    .iter()
+                    .find(|object| object.build_id.as_ref() == Some(id)),
+                None => self
+                    .objects
+                    .iter()
+                    .find(|object| object.path.file_name() == module.path.file_name()),
+            }
+        }
+    }
+
+    impl Default for Symbolizer {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl fmt::Debug for Symbolizer {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("Symbolizer")
+                .field(
+                    "objects",
+                    &self.objects.iter().map(|o| &o.path).collect::<Vec<_>>(),
+                )
+                .finish()
+        }
+    }
+
+    type Resolved = Vec<(Option<String>, Option<PathBuf>, Option<u32>)>;
+
+    struct ResolvedObject<'a> {
+        object: object::File<'a>,
+        base: u64,
+        context: Option<
+            addr2line::Context<addr2line::gimli::EndianRcSlice<addr2line::gimli::RunTimeEndian>>,
+        >,
+    }
+
+    impl<'a> ResolvedObject<'a> {
+        fn load(object: &'a LoadedObject) -> Option<Self> {
+            let object = object::File::parse(&*object.data).ok()?;
+            let base = load_base(&object);
+            let context = addr2line::Context::new(&object).ok();
+
+            Some(ResolvedObject {
+                object,
+                base,
+                context,
+            })
+        }
+
+        fn resolve(&self, frame: &RawFrame) -> Resolved {
+            // Return addresses point at the instruction after the call.
+            let probe = (frame.address as u64)
+                .saturating_sub(1)
+                .wrapping_add(self.base);
+
+            let mut resolved = Resolved::new();
+
+            if let Some(context) = &self.context {
+                if let Ok(mut iter) = context.find_frames(probe).skip_all_loads() {
+                    while let Ok(Some(frame)) = iter.next() {
+                        let name = frame
+                            .function
+                            .as_ref()
+                            .and_then(|name| name.demangle().ok())
+                            .map(|name| name.into_owned());
+                        let (filename, lineno) = match frame.location {
+                            Some(location) => (location.file.map(PathBuf::from), location.line),
+                            None => (None, None),
+                        };
+
+                        resolved.push((name, filename, lineno));
+                    }
+                }
+            }
+
+            // Fall back to the symbol table for objects without debuginfo.
+            if resolved.is_empty() {
+                let symbols = self.object.symbol_map();
+                if let Some(symbol) = symbols.get(probe) {
+                    let name = rustc_demangle::demangle(symbol.name()).to_string();
+                    resolved.push((Some(name), None, None));
+                }
+            }
+
+            resolved
+        }
+    }
+
+    /// The address the start of the mapping of `object` is linked at, which
+    /// the offsets of a raw backtrace are relative to
+    fn load_base(object: &object::File<'_>) -> u64 {
+        use object::ObjectSegment;
+
+        match object.format() {
+            // The loader maps ELF objects from the page of their first
+            // segment, which is 0 for position independent executables and
+            // shared objects but not for other executables.
+            object::BinaryFormat::Elf => object
+                .segments()
+                .map(|segment| segment.address())
+                .min()
+                .map_or(0, |address| address & !0xfff),
+            _ => object.relative_address_base(),
+        }
+    }
+
+    /// A resolved backtrace, displayed the same way as backtraces in error
+    /// reports
+    #[cfg_attr(docsrs, doc(cfg(feature = "symbolize")))]
+    pub struct SymbolizedBacktrace<'a> {
+        symbolizer: &'a Symbolizer,
+        frames: Vec<Frame>,
+    }
+
+    impl SymbolizedBacktrace<'_> {
+        /// Returns the resolved frames, before filtering
+        pub fn frames(&self) -> &[Frame] {
+            &self.frames
+        }
+    }
+
+    impl fmt::Debug for SymbolizedBacktrace<'_> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("SymbolizedBacktrace")
+                .field("frames", &self.frames)
+                .finish()
+        }
+    }
+
+    impl fmt::Display for SymbolizedBacktrace<'_> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            FramesFormatter {
+                filters: &self.symbolizer.filters,
+                frames: &self.frames,
+                theme: self.symbolizer.theme,
+                spans: &[],
+                layout: TraceLayout::Separate,
+            }
+            .fmt(f)
+        }
+    }
+}
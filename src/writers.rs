@@ -1,4 +1,6 @@
 use crate::config::{lib_verbosity, panic_verbosity, Verbosity};
+#[cfg(feature = "capture-spantrace")]
+use crate::config::{SpanFilter, SpanTraceSection, Theme};
 use fmt::Write;
 use std::fmt::{self, Display};
 #[cfg(feature = "capture-spantrace")]
@@ -152,7 +154,11 @@ where
 }
 
 #[cfg(feature = "capture-spantrace")]
-pub(crate) struct FormattedSpanTrace<'a>(pub(crate) &'a SpanTrace);
+pub(crate) struct FormattedSpanTrace<'a>(
+    pub(crate) &'a SpanTrace,
+    pub(crate) &'a SpanFilter,
+    pub(crate) Theme,
+);
 
 #[cfg(feature = "capture-spantrace")]
 impl fmt::Display for FormattedSpanTrace<'_> {
@@ -164,7 +170,7 @@ impl fmt::Display for FormattedSpanTrace<'_> {
             write!(
                 indented(f).with_format(Format::Uniform { indentation: "  " }),
                 "{}",
-                color_spantrace::colorize(self.0)
+                SpanTraceSection(self.0, self.1, self.2)
             )?;
         }
 
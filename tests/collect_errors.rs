@@ -0,0 +1,33 @@
+use color_eyre::config::{HookBuilder, Theme};
+use color_eyre::section::{Aggregate, CollectErrors};
+use color_eyre::Handler;
+use std::num::ParseIntError;
+
+#[test]
+fn collect_values_or_errors() {
+    HookBuilder::blank().theme(Theme::new()).install().unwrap();
+
+    let numbers: Vec<u32> = ["1", "2", "3"]
+        .iter()
+        .map(|s| s.parse::<u32>())
+        .collect_errors("failed to parse the numbers")
+        .unwrap();
+    assert_eq!(numbers, [1, 2, 3]);
+
+    let report = (0..40)
+        .map(|n| format!("x{}", n).parse::<u32>())
+        .collect_errors::<Vec<_>, _>(Aggregate::new("failed to parse the numbers").limit(3))
+        .unwrap_err();
+    assert_eq!(report.to_string(), "failed to parse the numbers");
+
+    let output = format!("{:?}", report);
+    assert_eq!(output.matches("invalid digit found in string").count(), 3);
+    assert!(output.contains("\n\n... and 37 more"));
+
+    let handler = report.handler().downcast_ref::<Handler>().unwrap();
+    let errors: Vec<_> = handler
+        .errors()
+        .filter_map(|error| error.downcast_ref::<ParseIntError>())
+        .collect();
+    assert_eq!(errors.len(), 40);
+}
@@ -0,0 +1,26 @@
+use color_eyre::section::{Aggregate, CollectErrors};
+
+#[test]
+fn errors_are_kept_without_the_handler() {
+    // Without `color_eyre::install`, reports use eyre's default handler.
+    let report = ["1", "two", "", "four"]
+        .iter()
+        .map(|s| s.parse::<u32>())
+        .collect_errors::<Vec<_>, _>(Aggregate::new("failed to parse the numbers").limit(2))
+        .unwrap_err();
+
+    assert!(report
+        .handler()
+        .downcast_ref::<color_eyre::Handler>()
+        .is_none());
+    assert_eq!(report.to_string(), "failed to parse the numbers");
+
+    let errors: Vec<_> = report.chain().skip(1).map(|e| e.to_string()).collect();
+    assert_eq!(
+        errors,
+        ["invalid digit found in string\n\
+          cannot parse integer from empty string\n\
+          ... and 1 more"]
+    );
+    assert!(format!("{:?}", report).contains("cannot parse integer from empty string"));
+}
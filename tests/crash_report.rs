@@ -0,0 +1,204 @@
+#![cfg(feature = "crash-report")]
+use color_eyre::crash_report::{CrashReporter, Delivery, HttpTransport, Transport};
+use color_eyre::eyre::eyre;
+use color_eyre::Section;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// An in-process endpoint that records the reports it receives
+#[derive(Clone, Default)]
+struct Endpoint {
+    offline: Arc<AtomicBool>,
+    received: Arc<Mutex<Vec<String>>>,
+}
+
+impl Transport for Endpoint {
+    fn send(&self, _url: &str, body: &str) -> io::Result<()> {
+        if self.offline.load(Ordering::SeqCst) {
+            return Err(io::ErrorKind::ConnectionRefused.into());
+        }
+
+        self.received.lock().unwrap().push(body.to_string());
+        Ok(())
+    }
+}
+
+#[test]
+fn send_spool_and_resend() {
+    let spool_dir = std::env::temp_dir().join(format!("color-eyre-spool-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&spool_dir);
+
+    let endpoint = Endpoint::default();
+    let reporter = || {
+        CrashReporter::with_transport("http://crashes.invalid/report", endpoint.clone())
+            .retry_delay(Duration::from_millis(1))
+            .spool_dir(&spool_dir)
+    };
+
+    color_eyre::config::HookBuilder::default()
+        .add_redaction_rule(Box::new(|text: &mut String| {
+            *text = text.replace("hunter2", "[redacted]")
+        }))
+        .crash_reporter(reporter())
+        .install()
+        .unwrap();
+
+    let report = eyre!("invalid password \"hunter2\"")
+        .wrap_err("failed to log in")
+        .field("user", "admin")
+        .field("attempt", 3)
+        .code("E0042");
+    assert_eq!(
+        color_eyre::crash_report::send(&report).unwrap(),
+        Some(Delivery::Sent)
+    );
+
+    let body = endpoint.received.lock().unwrap().pop().unwrap();
+    assert!(body.starts_with(r#"{"kind":"error","message":"failed to log in","causes":["invalid password \"[redacted]\""]"#));
+    assert!(
+        body.contains(r#""fields":{"user":"admin","attempt":3},"code":"E0042","docs_url":null"#)
+    );
+    assert!(body.contains(r#""fingerprint":""#));
+    assert!(!body.contains("hunter2"));
+
+    endpoint.offline.store(true, Ordering::SeqCst);
+    let path = match color_eyre::crash_report::send(&report).unwrap() {
+        Some(Delivery::Spooled(path)) => path,
+        delivery => panic!("unexpected delivery: {:?}", delivery),
+    };
+    let spooled = std::fs::read_to_string(&path).unwrap();
+    assert!(spooled.starts_with(r#"{"kind":"error","message":"failed to log in""#));
+
+    // The next start sends the spooled report.
+    endpoint.offline.store(false, Ordering::SeqCst);
+    assert_eq!(reporter().send_spooled().unwrap(), 1);
+    assert!(!path.exists());
+    assert_eq!(endpoint.received.lock().unwrap().len(), 1);
+
+    std::fs::remove_dir_all(&spool_dir).unwrap();
+}
+
+#[test]
+fn http_transport() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/report", listener.local_addr().unwrap());
+
+    let server = std::thread::spawn(move || {
+        let mut requests = Vec::new();
+        for response in &[
+            "HTTP/1.1 204 No Content",
+            "HTTP/1.1 503 Service Unavailable",
+        ] {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+
+            let mut head = String::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(length) = line.strip_prefix("Content-Length: ") {
+                    content_length = length.trim().parse().unwrap();
+                }
+                if line == "\r\n" {
+                    break;
+                }
+                head.push_str(&line);
+            }
+
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            write!(reader.get_mut(), "{}\r\n\r\n", response).unwrap();
+            requests.push((head, String::from_utf8(body).unwrap()));
+        }
+        requests
+    });
+
+    let transport = HttpTransport::new().timeout(Duration::from_secs(5));
+    transport.send(&url, r#"{"kind":"panic"}"#).unwrap();
+    let error = transport.send(&url, "{}").unwrap_err();
+    assert!(error.to_string().contains("503 Service Unavailable"));
+
+    let requests = server.join().unwrap();
+    assert!(requests[0].0.starts_with("POST /report HTTP/1.1\r\n"));
+    assert!(requests[0].0.contains("Content-Type: application/json\r\n"));
+    assert_eq!(requests[0].1, r#"{"kind":"panic"}"#);
+
+    let error = transport.send("https://example.com", "{}").unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn http_transport_stops_at_the_timeout() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/report", listener.local_addr().unwrap());
+
+    let transport = HttpTransport::new().timeout(Duration::from_millis(0));
+    let error = transport.send(&url, "{}").unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::TimedOut);
+}
+
+#[test]
+fn urls_are_checked_when_built() {
+    let error = CrashReporter::new("https://crashes.example.com/report").unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    assert!(error
+        .to_string()
+        .contains("doesn't support `https://` urls"));
+    assert!(error.to_string().contains("CrashReporter::with_transport"));
+
+    let error = CrashReporter::new("crashes.example.com/report").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "`HttpTransport` only supports `http://` urls"
+    );
+
+    CrashReporter::new("http://[::1]:8080/report").unwrap();
+    CrashReporter::with_transport("https://crashes.example.com/report", Endpoint::default());
+}
+
+#[test]
+fn corrupt_spooled_reports_are_skipped() {
+    let spool_dir = std::env::temp_dir().join(format!("color-eyre-corrupt-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&spool_dir);
+    std::fs::create_dir_all(&spool_dir).unwrap();
+
+    let spooled = |n: u32, name: &str| spool_dir.join(format!("{:020}-{}.json", n, name));
+    std::fs::create_dir(spooled(1, "directory")).unwrap();
+    std::fs::write(spooled(2, "cut-short"), r#"{"kind":"panic","mess"#).unwrap();
+    std::fs::write(spooled(3, "binary"), [b'{', 0xff, 0xfe, b'}']).unwrap();
+    std::fs::write(spooled(4, "valid"), r#"{"kind":"panic"}"#).unwrap();
+    std::fs::write(spooled(5, "valid"), r#"{"kind":"error"}"#).unwrap();
+
+    let endpoint = Endpoint::default();
+    let reporter = CrashReporter::with_transport("http://crashes.invalid/report", endpoint.clone())
+        .spool_dir(&spool_dir);
+
+    assert_eq!(reporter.send_spooled().unwrap(), 2);
+    assert_eq!(
+        *endpoint.received.lock().unwrap(),
+        vec![r#"{"kind":"panic"}"#, r#"{"kind":"error"}"#]
+    );
+
+    let mut left: Vec<_> = std::fs::read_dir(&spool_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    left.sort();
+    assert_eq!(
+        left,
+        vec![
+            "00000000000000000001-directory.json.corrupt",
+            "00000000000000000002-cut-short.json.corrupt",
+            "00000000000000000003-binary.json.corrupt",
+        ]
+    );
+
+    // Quarantined files aren't tried again.
+    assert_eq!(reporter.send_spooled().unwrap(), 0);
+
+    std::fs::remove_dir_all(&spool_dir).unwrap();
+}
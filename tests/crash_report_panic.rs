@@ -0,0 +1,59 @@
+#![cfg(feature = "crash-report")]
+use color_eyre::crash_report::{CrashReporter, Transport};
+use std::io;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// The timeout of each attempt to send a report, `None` for attempts without
+/// one
+static ATTEMPTS: Mutex<Vec<Option<Duration>>> = Mutex::new(Vec::new());
+
+struct Offline;
+
+impl Transport for Offline {
+    fn send(&self, _url: &str, _body: &str) -> io::Result<()> {
+        ATTEMPTS.lock().unwrap().push(None);
+        Err(io::ErrorKind::ConnectionRefused.into())
+    }
+
+    fn send_with_timeout(&self, _url: &str, _body: &str, timeout: Duration) -> io::Result<()> {
+        ATTEMPTS.lock().unwrap().push(Some(timeout));
+        Err(io::ErrorKind::TimedOut.into())
+    }
+}
+
+#[test]
+fn panics_are_attempted_once() {
+    std::env::set_var("RUST_BACKTRACE", "0");
+
+    let spool_dir =
+        std::env::temp_dir().join(format!("color-eyre-panic-spool-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&spool_dir);
+
+    color_eyre::config::HookBuilder::blank()
+        .crash_reporter(
+            CrashReporter::with_transport("http://crashes.invalid/report", Offline)
+                .retries(5)
+                .retry_delay(Duration::from_secs(10))
+                .panic_timeout(Duration::from_millis(300))
+                .spool_dir(&spool_dir),
+        )
+        .install()
+        .unwrap();
+
+    let start = Instant::now();
+    std::panic::catch_unwind(|| panic!("it broke")).unwrap_err();
+
+    // A single attempt without retrying, then the report is spooled. The
+    // spool may also be sent by `install` in the background, with `send`.
+    assert!(start.elapsed() < Duration::from_secs(10));
+    let attempts: Vec<_> = ATTEMPTS.lock().unwrap().iter().flatten().cloned().collect();
+    assert_eq!(attempts, vec![Duration::from_millis(300)]);
+
+    let spooled: Vec<_> = std::fs::read_dir(&spool_dir).unwrap().collect();
+    assert_eq!(spooled.len(), 1);
+    let body = std::fs::read_to_string(spooled[0].as_ref().unwrap().path()).unwrap();
+    assert!(body.starts_with(r#"{"kind":"panic","message":"it broke""#));
+
+    std::fs::remove_dir_all(&spool_dir).unwrap();
+}
@@ -0,0 +1,32 @@
+use color_eyre::config::{HookBuilder, RedundantErrors, ReportLayout, Theme};
+use color_eyre::eyre::{eyre, WrapErr};
+use color_eyre::Section;
+
+#[test]
+fn repeated_sections_and_redundant_errors() {
+    HookBuilder::blank()
+        .theme(Theme::new())
+        .report_layout(
+            ReportLayout::new()
+                .deduplicate_sections(true)
+                .redundant_errors(RedundantErrors::Elide),
+        )
+        .install()
+        .unwrap();
+
+    let report = Err::<(), _>(eyre!("No such file or directory"))
+        .wrap_err("failed to read config: No such file or directory")
+        .wrap_err("failed to start")
+        .note("retrying")
+        .suggestion("check the path")
+        .note("retrying")
+        .note("retrying")
+        .unwrap_err();
+    let output = format!("{:?}", report);
+
+    assert!(output.contains(
+        "\n   0: failed to start\n   1: failed to read config: No such file or directory\n\n"
+    ));
+    assert!(output.contains("Note: retrying (×3)\nSuggestion: check the path"));
+    assert_eq!(output.matches("retrying").count(), 1);
+}
@@ -0,0 +1,16 @@
+use color_eyre::config::{HookBuilder, Theme};
+use color_eyre::owo_colors::style;
+use color_eyre::section::DefinedKind;
+use color_eyre::{eyre::eyre, Section};
+
+#[test]
+fn blank_theme_prints_labels_without_style() {
+    HookBuilder::blank().theme(Theme::new()).install().unwrap();
+
+    let docs = DefinedKind::new("Docs").style(style().cyan().bold());
+    let report = eyre!("failed to parse the config").labeled(docs, "https://example.com/docs");
+    let output = format!("{:?}", report);
+
+    assert!(output.contains("Docs: https://example.com/docs"));
+    assert!(!output.contains('\u{1b}'), "{:?}", output);
+}
@@ -0,0 +1,34 @@
+use color_eyre::config::{HookBuilder, Theme};
+use color_eyre::eyre::{eyre, Report};
+use color_eyre::{Handler, Section};
+
+#[test]
+fn error_codes_and_docs_links() {
+    HookBuilder::blank()
+        .theme(Theme::new())
+        .error_docs_url("https://docs.example.com/errors/{code}")
+        .install()
+        .unwrap();
+
+    let report: Report = Err::<(), _>(eyre!("the config file is missing"))
+        .code("E0001")
+        .code("E0042")
+        .unwrap_err();
+    let output = format!("{:?}", report);
+    assert!(output.starts_with(
+        "\n   Code: E0042 (https://docs.example.com/errors/E0042)\n   0: the config file is missing"
+    ));
+
+    let handler = report.handler().downcast_ref::<Handler>().unwrap();
+    assert_eq!(handler.code(), Some("E0042"));
+    assert_eq!(
+        handler.docs_url().as_deref(),
+        Some("https://docs.example.com/errors/E0042")
+    );
+
+    let report = eyre!("no code");
+    let handler = report.handler().downcast_ref::<Handler>().unwrap();
+    assert_eq!(handler.code(), None);
+    assert_eq!(handler.docs_url(), None);
+    assert!(!format!("{:?}", report).contains("Code:"));
+}
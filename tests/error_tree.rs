@@ -0,0 +1,36 @@
+use color_eyre::config::{HookBuilder, ReportLayout, Theme};
+use color_eyre::{eyre::eyre, Section};
+
+#[derive(Debug, thiserror::Error)]
+#[error("row {0} is invalid")]
+struct InvalidRow(usize, #[source] std::io::Error);
+
+#[test]
+fn attached_errors_are_printed_as_a_tree() {
+    HookBuilder::blank()
+        .theme(Theme::new())
+        .report_layout(ReportLayout::new().error_tree(true))
+        .install()
+        .unwrap();
+
+    let line = line!() + 2;
+    let report = eyre!("failed to import the file")
+        .error(InvalidRow(3, std::io::Error::other("invalid date")))
+        .error(InvalidRow(7, std::io::Error::other("missing column")));
+    let output = format!("{:?}", report);
+
+    let mut expected = String::from(concat!(
+        "Errors:\n",
+        "├─ 1: row 3 is invalid\n",
+        "│     caused by: invalid date",
+    ));
+    if cfg!(feature = "track-caller") {
+        expected.push_str(&format!("\n│     at tests/error_tree.rs:{}", line));
+    }
+    expected.push_str("\n└─ 2: row 7 is invalid\n      caused by: missing column");
+    if cfg!(feature = "track-caller") {
+        expected.push_str(&format!("\n      at tests/error_tree.rs:{}", line + 1));
+    }
+
+    assert!(output.contains(&expected), "{}", output);
+}
@@ -0,0 +1,32 @@
+use color_eyre::{eyre, Handler};
+use eyre::{eyre, Report};
+
+fn read_config(path: &str, retries: u32) -> Report {
+    eyre!("failed to open \"{}\" after {} retries", path, retries).wrap_err("loading config")
+}
+
+#[test]
+fn fingerprint() {
+    color_eyre::config::HookBuilder::default()
+        .display_fingerprint(true)
+        .install()
+        .unwrap();
+
+    let fingerprint = |report: &Report| {
+        report
+            .handler()
+            .downcast_ref::<Handler>()
+            .unwrap()
+            .fingerprint(&**report)
+    };
+
+    let first = read_config("/etc/app.toml", 3);
+    let second = read_config("/home/user/app.toml", 7);
+    let other = eyre!("unexpected end of input");
+
+    assert_eq!(fingerprint(&first), fingerprint(&second));
+    assert_ne!(fingerprint(&first), fingerprint(&other));
+
+    let report = format!("{:?}", first);
+    assert!(report.contains(&format!("Fingerprint: {}", fingerprint(&first))));
+}
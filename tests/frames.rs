@@ -0,0 +1,104 @@
+use color_eyre::config::{Frame, HookBuilder, Theme};
+use color_eyre::eyre::eyre;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+#[derive(Debug)]
+struct Recorded {
+    n: usize,
+    inlined: bool,
+    name: Option<String>,
+    ip: Option<usize>,
+    module: Option<PathBuf>,
+    module_base: Option<usize>,
+}
+
+static FRAMES: Mutex<Vec<Recorded>> = Mutex::new(Vec::new());
+
+fn record(frames: &mut Vec<&Frame>) {
+    let mut recorded = FRAMES.lock().unwrap();
+    if !recorded.is_empty() {
+        return;
+    }
+
+    *recorded = frames
+        .iter()
+        .map(|frame| Recorded {
+            n: frame.n,
+            inlined: frame.inlined,
+            name: frame.name.clone(),
+            ip: frame.ip,
+            module: frame.module.clone(),
+            module_base: frame.module_base,
+        })
+        .collect();
+}
+
+#[test]
+#[cfg(unix)]
+fn physical_frames_are_numbered_and_inlined_symbols_nested() {
+    std::env::set_var("RUST_LIB_BACKTRACE", "full");
+
+    HookBuilder::blank()
+        .theme(Theme::new())
+        .add_frame_filter(Box::new(record))
+        .install()
+        .unwrap();
+
+    let output = format!("{:?}", eyre!("error occured"));
+    let frames = std::mem::take(&mut *FRAMES.lock().unwrap());
+    assert!(!frames.is_empty());
+
+    // Physical frames are numbered from 1 without gaps, and inlined symbols
+    // follow the physical frame they share a number with.
+    let mut last_n = 0;
+    for frame in &frames {
+        if frame.inlined {
+            assert_eq!(frame.n, last_n, "{:?}", frame);
+        } else {
+            assert_eq!(frame.n, last_n + 1, "{:?}", frame);
+        }
+        last_n = frame.n;
+    }
+
+    assert!(!output.contains("[inlined]"));
+    let numbered = output
+        .lines()
+        .filter_map(|line| line.trim_start().split_once(": "))
+        .filter_map(|(n, _)| n.parse::<usize>().ok())
+        .collect::<Vec<_>>();
+    assert!(!numbered.is_empty());
+    assert!(numbered.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(numbered.iter().all(|n| *n <= last_n));
+
+    for frame in frames.iter().filter(|frame| frame.inlined) {
+        let name = frame.name.as_deref().unwrap();
+        let name = name.get(..20).unwrap_or(name);
+        // Two spaces of section indentation, six to nest under the frame.
+        assert!(output.contains(&format!("\n        {}", name)), "{}", name);
+    }
+
+    // Frames of this test are in the test executable, at an offset inside
+    // it. Frames of shared objects, like libc's, keep their own path.
+    let exe = std::env::current_exe().unwrap();
+    let size = std::fs::metadata(&exe).unwrap().len() as usize;
+    let this = frames
+        .iter()
+        .find(|frame| {
+            frame.name.as_deref().is_some_and(|name| {
+                name.contains("physical_frames_are_numbered_and_inlined_symbols_nested")
+            })
+        })
+        .unwrap();
+    assert_eq!(this.module.as_ref(), Some(&exe));
+    let offset = this.ip.unwrap() - this.module_base.unwrap();
+    assert!(offset < size, "{:#x} >= {:#x}", offset, size);
+    assert!(output.contains(&format!("in {} +{:#x}", exe.display(), offset)));
+
+    for frame in &frames {
+        if let Some(module) = frame.module.as_ref().filter(|module| **module != exe) {
+            assert!(module.is_absolute(), "{:?}", frame);
+            assert!(frame.module_base != this.module_base, "{:?}", frame);
+        }
+    }
+}
@@ -0,0 +1,63 @@
+#![cfg(feature = "issue-url")]
+use color_eyre::eyre::eyre;
+use color_eyre::issue::{IssueAction, IssueBundle};
+use std::path::{Path, PathBuf};
+
+fn bundles(dir: &Path) -> Vec<PathBuf> {
+    match std::fs::read_dir(dir) {
+        Ok(entries) => entries.map(|entry| entry.unwrap().path()).collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+#[test]
+fn bundles_are_only_saved_on_request() {
+    std::env::set_var("RUST_LIB_BACKTRACE", "0");
+    std::env::set_var("RUST_BACKTRACE", "0");
+
+    let dir = std::env::temp_dir().join(format!("color-eyre-bundles-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    color_eyre::config::HookBuilder::blank()
+        .add_issue_metadata("version", "1.2.3")
+        .issue_bundle(IssueBundle::new(&dir).instructions("Send this file to us."))
+        .issue_filter(|context| match context.kind() {
+            color_eyre::ErrorKind::Recoverable(error) if error.to_string() == "ignored" => {
+                IssueAction::Skip
+            }
+            _ => IssueAction::Create,
+        })
+        .install()
+        .unwrap();
+
+    // Formatting has no side effects.
+    let report = eyre!("failed to load the config");
+    let first = format!("{:?}", report);
+    let second = format!("{:?}", report);
+    assert_eq!(first, second);
+    assert!(!first.contains("bug report"));
+    assert!(bundles(&dir).is_empty());
+
+    let path = color_eyre::issue::save_bundle(&report).unwrap();
+    assert_eq!(bundles(&dir), vec![path.clone()]);
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert!(contents.starts_with("# Bug report\n\n## Error\n"));
+    assert!(contents.contains("0: failed to load the config"));
+    assert!(contents.contains("|**version**|1.2.3|"));
+    assert!(contents.contains("## Environment\n"));
+
+    let error = color_eyre::issue::save_bundle(&eyre!("ignored")).unwrap_err();
+    assert_eq!(error.to_string(), "the issue filter skipped this report");
+    assert_eq!(bundles(&dir).len(), 1);
+
+    // The panic hook saves a single bundle per panic.
+    std::panic::catch_unwind(|| panic!("it broke")).unwrap_err();
+    let mut saved = bundles(&dir);
+    saved.retain(|saved| *saved != path);
+    assert_eq!(saved.len(), 1);
+    assert!(std::fs::read_to_string(&saved[0])
+        .unwrap()
+        .contains("it broke"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,54 @@
+#![cfg(feature = "issue-url")]
+use color_eyre::eyre::eyre;
+use color_eyre::issue::IssueBundle;
+
+#[test]
+fn environment_is_included() {
+    std::env::set_var("RUST_LIB_BACKTRACE", "0");
+    std::env::set_var("RUST_SPANTRACE", "0");
+    std::env::set_var("AWS_SECRET_ACCESS_KEY", "wJalrXUtnFEMI");
+    std::env::set_var("DATABASE_URL", "postgres://admin:hunter2@db/app");
+
+    let dir = std::env::temp_dir().join(format!("color-eyre-env-{}", std::process::id()));
+
+    color_eyre::config::HookBuilder::blank()
+        .issue_bundle(IssueBundle::new(&dir))
+        .install()
+        .unwrap();
+
+    let path = color_eyre::issue::save_bundle(&eyre!("failed to start")).unwrap();
+    let contents = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let environment = contents
+        .split("## Environment\n")
+        .nth(1)
+        .unwrap()
+        .split("\n## ")
+        .next()
+        .unwrap();
+
+    // The toolchain and system are the same as in `Environment`.
+    let entries = color_eyre::environment::Environment::new("app", "1.0.0").entries();
+    for key in &["rustc", "target", "os", "arch"] {
+        let value = &entries.iter().find(|(name, _)| name == key).unwrap().1;
+        assert!(
+            environment.contains(&format!("|**{}**|{}|\n", key, value)),
+            "{}",
+            environment
+        );
+    }
+    assert!(!environment.contains("|unknown|"), "{}", environment);
+
+    // Only the allow-listed variables are included.
+    assert!(environment.contains("|**RUST_LIB_BACKTRACE**|0|\n"));
+    assert!(environment.contains("|**RUST_SPANTRACE**|0|\n"));
+    for secret in &[
+        "AWS_SECRET_ACCESS_KEY",
+        "wJalrXUtnFEMI",
+        "DATABASE_URL",
+        "hunter2",
+    ] {
+        assert!(!contents.contains(secret), "{}", contents);
+    }
+}
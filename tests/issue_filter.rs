@@ -0,0 +1,39 @@
+#![cfg(feature = "issue-url")]
+use color_eyre::eyre::eyre;
+use color_eyre::issue::{IssueAction, IssueDecision};
+use color_eyre::section::SectionKind;
+use color_eyre::Section;
+
+#[test]
+fn issue_filter_context() {
+    std::env::set_var("RUST_LIB_BACKTRACE", "1");
+
+    color_eyre::config::HookBuilder::default()
+        .issue_url("https://github.com/yaahc/color-eyre/issues/new")
+        .issue_filter(|context| {
+            assert!(context.location().is_some());
+            assert!(context.has_frame_from("issue-filter"));
+            assert!(!context.has_frame_from("some_plugin"));
+
+            let suggestion = context
+                .sections()
+                .find(|section| section.kind() == SectionKind::Suggestion);
+
+            match suggestion {
+                Some(suggestion) => IssueDecision::new(IssueAction::Create)
+                    .label("has-suggestion")
+                    .metadata("suggestion", suggestion),
+                None => IssueDecision::new(IssueAction::Skip),
+            }
+        })
+        .install()
+        .unwrap();
+
+    let report = eyre!("unsupported input").suggestion("try a smaller file");
+    let report = format!("{:?}", report);
+    assert!(report.contains("labels=has-suggestion"));
+    assert!(report.contains("%7C**suggestion**%7Ctry+a+smaller+file%7C"));
+
+    let report = format!("{:?}", eyre!("unsupported input"));
+    assert!(!report.contains("Consider reporting this error"));
+}
@@ -0,0 +1,131 @@
+#![cfg(feature = "issue-url")]
+use color_eyre::eyre::eyre;
+use color_eyre::issue::{IssueAction, IssuePrompt};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// The urls opened in the browser
+static OPENED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+fn save_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("color-eyre-prompt-{}", std::process::id()))
+}
+
+fn install() {
+    static INSTALL: std::sync::Once = std::sync::Once::new();
+
+    INSTALL.call_once(|| {
+        std::env::set_var("RUST_LIB_BACKTRACE", "0");
+
+        color_eyre::config::HookBuilder::blank()
+            .issue_url("https://github.com/owner/repo/issues/new")
+            .issue_filter(|context| match context.kind() {
+                color_eyre::ErrorKind::Recoverable(error) if error.to_string() == "known" => {
+                    IssueAction::Search
+                }
+                _ => IssueAction::Create,
+            })
+            .issue_prompt(
+                IssuePrompt::new()
+                    .save_dir(save_dir())
+                    .opener(Box::new(|url| {
+                        OPENED.lock().unwrap().push(url.to_string());
+                        Ok(())
+                    })),
+            )
+            .install()
+            .unwrap();
+    });
+}
+
+/// Prompt for an issue for an error with `message`, answering with `input`,
+/// and return the prompt and the url opened, if any
+fn prompt(message: &str, input: &str) -> (String, Option<String>) {
+    install();
+
+    let report = eyre!("{}", message);
+    let mut output = Vec::new();
+    color_eyre::issue::prompt_with(&report, &mut input.as_bytes(), &mut output).unwrap();
+
+    let opened = OPENED.lock().unwrap();
+    let url = opened
+        .iter()
+        .find(|url| url.contains(message) || url.ends_with(&report_fingerprint(&report)))
+        .cloned();
+
+    (String::from_utf8(output).unwrap(), url)
+}
+
+fn report_fingerprint(report: &color_eyre::Report) -> String {
+    report
+        .handler()
+        .downcast_ref::<color_eyre::Handler>()
+        .unwrap()
+        .fingerprint(&**report)
+        .to_string()
+}
+
+#[test]
+fn yes_opens_the_issue() {
+    let (output, url) = prompt("prompt_yes", "y\n");
+
+    assert!(output.starts_with("\nThis is the issue that would be submitted:\n\n"));
+    assert!(output.contains("Title: <autogenerated-issue>\n\n## Error\n"));
+    assert!(output.contains("0: prompt_yes\n"), "{}", output);
+    assert!(
+        output.ends_with("Open this issue in the browser? [y]es, [s]ave the url to a file, [N]o: ")
+    );
+
+    let url = url.unwrap();
+    assert!(url.starts_with("https://github.com/owner/repo/issues/new?title="));
+}
+
+#[test]
+fn no_does_nothing() {
+    for answer in &["n\n", "no\n", "\n", "maybe\n"] {
+        let (output, url) = prompt("prompt_no", answer);
+
+        assert!(output.ends_with("[N]o: "));
+        assert_eq!(url, None);
+    }
+}
+
+#[test]
+fn end_of_input_does_nothing() {
+    let (output, url) = prompt("prompt_eof", "");
+
+    assert!(output.ends_with("[N]o: "));
+    assert_eq!(url, None);
+}
+
+#[test]
+fn save_writes_the_url_to_a_file() {
+    let (output, url) = prompt("prompt_save", "s\n");
+
+    assert_eq!(url, None);
+    let path = output
+        .split("Saved the issue url to: ")
+        .nth(1)
+        .unwrap()
+        .trim_end();
+    assert!(path.starts_with(save_dir().to_str().unwrap()));
+
+    let saved = std::fs::read_to_string(path).unwrap();
+    assert!(saved.starts_with("https://github.com/owner/repo/issues/new?title="));
+    assert!(saved.contains("prompt_save"));
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn search_asks_to_search() {
+    let (output, url) = prompt("known", "yes\n");
+
+    // Searches have no preview.
+    assert_eq!(
+        output,
+        "Search for existing reports of this error? [y]es, [s]ave the url to a file, [N]o: "
+    );
+    assert!(url
+        .unwrap()
+        .starts_with("https://github.com/owner/repo/issues?q=is%3Aissue+"));
+}
@@ -0,0 +1,90 @@
+#![cfg(feature = "issue-url")]
+use color_eyre::eyre::eyre;
+use color_eyre::issue::IssueTemplate;
+
+/// The title and body of the issue generated for `report`
+fn issue(report: color_eyre::Report) -> (String, String) {
+    let report = format!("{:?}", report);
+    let url = report
+        .split("Consider reporting this error using this URL: ")
+        .nth(1)
+        .unwrap()
+        .trim_end();
+    let url = url::Url::parse(url).unwrap();
+    let param = |name: &str| {
+        url.query_pairs()
+            .find(|(key, _)| key == name)
+            .unwrap()
+            .1
+            .into_owned()
+    };
+
+    (param("title"), param("body"))
+}
+
+fn install() {
+    static INSTALL: std::sync::Once = std::sync::Once::new();
+
+    INSTALL.call_once(|| {
+        std::env::set_var("RUST_LIB_BACKTRACE", "0");
+
+        color_eyre::config::HookBuilder::blank()
+            .issue_url("https://github.com/owner/repo/issues/new")
+            .add_issue_metadata("version", "1.2.3")
+            .issue_template(
+                IssueTemplate::new()
+                    .title("[{version}] {message} ({message})")
+                    .body(
+                        "{message}|{version}|{version}\n\
+                         unknown: {build} {Message} {} {error\n\
+                         escaped: {{message}} }} {{\n\
+                         stray: } { {nope {message}}\n\
+                         {error}",
+                    ),
+            )
+            .install()
+            .unwrap();
+    });
+}
+
+#[test]
+fn placeholders_are_filled() {
+    install();
+
+    let (title, body) = issue(eyre!("parse failed"));
+
+    // Repeated placeholders are filled every time.
+    assert_eq!(title, "[1.2.3] parse failed (parse failed)");
+    let mut lines = body.lines();
+    assert_eq!(lines.next(), Some("parse failed|1.2.3|1.2.3"));
+    // Unknown placeholders are kept as they are.
+    assert_eq!(lines.next(), Some("unknown: {build} {Message} {} {error"));
+    // Doubled braces are escaped.
+    assert_eq!(lines.next(), Some("escaped: {message} } {"));
+    // So are single braces that don't start or end a known placeholder.
+    assert_eq!(lines.next(), Some("stray: } { {nope parse failed}"));
+    assert_eq!(lines.next(), Some("```"));
+    assert_eq!(lines.next(), Some("Error: "));
+}
+
+#[test]
+fn values_with_braces_are_not_filled() {
+    install();
+
+    let (title, body) = issue(eyre!("bad input {{version}} in {{\"a\": {{}}}}"));
+
+    assert_eq!(
+        title,
+        "[1.2.3] bad input {version} in {\"a\": {}} (bad input {version} in {\"a\": {}})"
+    );
+    assert!(
+        body.starts_with("bad input {version} in {\"a\": {}}|1.2.3|1.2.3\n"),
+        "{}",
+        body
+    );
+    assert!(
+        body.ends_with("0: bad input {version} in {\"a\": {}}\n```"),
+        "{}",
+        body
+    );
+}
@@ -0,0 +1,68 @@
+#![cfg(feature = "issue-url")]
+use color_eyre::eyre::eyre;
+use color_eyre::issue::{IssueTemplate, Jira};
+
+#[test]
+fn new_issue_url() {
+    std::env::set_var("RUST_LIB_BACKTRACE", "0");
+
+    color_eyre::config::HookBuilder::blank()
+        .issue_url("https://jira.example.com/secure/CreateIssueDetails!init.jspa")
+        .issue_tracker(Jira::new(10000, 10004))
+        .add_issue_metadata("version", "1.2.3")
+        .issue_template(
+            IssueTemplate::new()
+                .title("crash: {message}")
+                .label("bug")
+                .label("crash")
+                .assignee("alice")
+                .assignee("bob")
+                .template("ignored.yml"),
+        )
+        .install()
+        .unwrap();
+
+    let report = format!("{:?}", eyre!("a&b failed"));
+    let url = report
+        .split("Consider reporting this error using this URL: ")
+        .nth(1)
+        .unwrap()
+        .trim_end();
+
+    assert!(
+        url.starts_with(
+            "https://jira.example.com/secure/CreateIssueDetails!init.jspa\
+             ?pid=10000&issuetype=10004&summary=crash%3A+a%26b+failed&description="
+        ),
+        "{}",
+        url
+    );
+    assert!(
+        url.ends_with("&labels=bug&labels=crash&assignee=alice"),
+        "{}",
+        url
+    );
+    assert!(!url.contains("ignored.yml"));
+
+    // The description is written in Jira's wiki markup.
+    let description = url::Url::parse(url)
+        .unwrap()
+        .query_pairs()
+        .find(|(key, _)| key == "description")
+        .unwrap()
+        .1
+        .into_owned();
+
+    assert!(
+        description.starts_with("h2. Error\n{noformat}\nError: \n   0: a&b failed\n{noformat}"),
+        "{}",
+        description
+    );
+    assert!(
+        description.contains("h2. Metadata\n||key||value||\n|*version*|1.2.3|\n"),
+        "{}",
+        description
+    );
+    assert!(!description.contains("```"));
+    assert!(!description.contains("<details>"));
+}
@@ -0,0 +1,121 @@
+#![cfg(feature = "issue-url")]
+use color_eyre::eyre::eyre;
+use color_eyre::issue::{GitHub, GitLab, Gitea, IssueTemplate, IssueTracker, Jira, NewIssue};
+use std::sync::Mutex;
+
+/// The new issue urls built by each tracker for the last report
+static URLS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Builds the url of the same issue with every Markdown tracker
+struct Trackers;
+
+impl IssueTracker for Trackers {
+    fn new_issue_url(&self, _url: &str, issue: &NewIssue<'_>) -> Option<String> {
+        let urls = vec![
+            GitHub.new_issue_url("https://github.com/owner/repo/issues/new", issue)?,
+            GitLab.new_issue_url("https://gitlab.com/group/project/-/issues/new", issue)?,
+            Gitea.new_issue_url("https://codeberg.org/owner/repo/issues/new", issue)?,
+        ];
+
+        let github = urls[0].clone();
+        *URLS.lock().unwrap() = urls;
+        Some(github)
+    }
+}
+
+#[test]
+fn new_issue_urls() {
+    std::env::set_var("RUST_LIB_BACKTRACE", "0");
+
+    color_eyre::config::HookBuilder::blank()
+        .issue_url("https://bugs.example.com/new")
+        .issue_tracker(Trackers)
+        .issue_template(
+            IssueTemplate::new()
+                .title("crash: {message}")
+                .body("Saw {message}")
+                .label("bug")
+                .label("needs triage")
+                .assignee("octocat")
+                .assignee("hubot")
+                .template("bug report.yml"),
+        )
+        .install()
+        .unwrap();
+
+    let report = format!("{:?}", eyre!("50% of a&b = c#d?"));
+    let urls = URLS.lock().unwrap();
+
+    assert_eq!(
+        urls[0],
+        "https://github.com/owner/repo/issues/new\
+         ?title=crash%3A+50%25+of+a%26b+%3D+c%23d%3F\
+         &body=Saw+50%25+of+a%26b+%3D+c%23d%3F\
+         &labels=bug%2Cneeds+triage\
+         &assignees=octocat%2Chubot\
+         &template=bug+report.yml"
+    );
+    assert_eq!(
+        urls[1],
+        "https://gitlab.com/group/project/-/issues/new\
+         ?issue%5Btitle%5D=crash%3A+50%25+of+a%26b+%3D+c%23d%3F\
+         &issue%5Bdescription%5D=Saw+50%25+of+a%26b+%3D+c%23d%3F\
+         %0A%0A%2Flabel+%7E%22bug%22+%7E%22needs+triage%22\
+         %0A%2Fassign+%40octocat+%40hubot\
+         &issuable_template=bug+report.yml"
+    );
+    assert_eq!(
+        urls[2],
+        "https://codeberg.org/owner/repo/issues/new\
+         ?title=crash%3A+50%25+of+a%26b+%3D+c%23d%3F\
+         &body=Saw+50%25+of+a%26b+%3D+c%23d%3F\
+         &labels=bug%2Cneeds+triage\
+         &template=bug+report.yml"
+    );
+    assert!(report.contains(&format!("this URL: {}", urls[0])));
+}
+
+#[test]
+fn search_urls() {
+    assert_eq!(
+        GitHub
+            .search_url("https://github.com/owner/repo/issues/new", "1f2e3d4c")
+            .unwrap(),
+        "https://github.com/owner/repo/issues?q=is%3Aissue+1f2e3d4c"
+    );
+    assert_eq!(
+        GitLab
+            .search_url("https://gitlab.com/group/project/-/issues/new", "1f2e3d4c")
+            .unwrap(),
+        "https://gitlab.com/group/project/-/issues?search=1f2e3d4c&state=all"
+    );
+    assert_eq!(
+        Gitea
+            .search_url(
+                "https://codeberg.org/owner/repo/issues/new?title=x",
+                "1f2e3d4c"
+            )
+            .unwrap(),
+        "https://codeberg.org/owner/repo/issues?q=1f2e3d4c&state=all"
+    );
+    assert_eq!(
+        Jira::new(10000, 10004)
+            .search_url(
+                "https://jira.example.com/secure/CreateIssueDetails!init.jspa",
+                "say \"1f2e3d4c\""
+            )
+            .unwrap(),
+        "https://jira.example.com/issues/\
+         ?jql=project+%3D+10000+AND+text+%7E+%22say+%5C%221f2e3d4c%5C%22%22"
+    );
+
+    // Urls that aren't new issue forms can't be turned into searches.
+    assert_eq!(
+        GitHub.search_url("https://github.com/owner/repo/issues", "1f2e3d4c"),
+        None
+    );
+    assert_eq!(
+        Jira::new(10000, 10004).search_url("https://jira.example.com/", "1f2e3d4c"),
+        None
+    );
+}
@@ -0,0 +1,92 @@
+#![cfg(feature = "issue-url")]
+use color_eyre::eyre::eyre;
+use color_eyre::Section;
+
+const MAX_LEN: usize = 1000;
+
+fn install() {
+    static INSTALL: std::sync::Once = std::sync::Once::new();
+
+    INSTALL.call_once(|| {
+        std::env::set_var("RUST_LIB_BACKTRACE", "1");
+
+        color_eyre::config::HookBuilder::blank()
+            .issue_url("https://github.com/owner/repo/issues/new")
+            .add_issue_metadata("version", "1.2.3")
+            .issue_url_max_length(MAX_LEN)
+            .install()
+            .unwrap();
+    });
+}
+
+/// The issue url and its decoded body
+fn issue(report: color_eyre::Report) -> (String, String) {
+    let report = format!("{:?}", report);
+    let url = report
+        .split("Consider reporting this error using this URL: ")
+        .nth(1)
+        .unwrap()
+        .trim_end()
+        .to_string();
+    let body = url::Url::parse(&url)
+        .unwrap()
+        .query_pairs()
+        .find(|(key, _)| key == "body")
+        .unwrap()
+        .1
+        .into_owned();
+
+    (url, body)
+}
+
+#[test]
+fn long_issues_are_cut_to_fit() {
+    install();
+
+    let (url, body) = issue(eyre!("disk full").note("x".repeat(2000)));
+
+    assert!(url.len() <= MAX_LEN, "{} > {}", url.len(), MAX_LEN);
+    assert!(
+        body.starts_with("## Error\n```\nError: \n   0: disk full\n```"),
+        "{}",
+        body
+    );
+    assert!(body.contains("|**version**|1.2.3|"), "{}", body);
+    assert!(body.contains("## Sections"), "{}", body);
+    assert!(
+        body.ends_with(
+            "_Some information was left out to keep the url of this issue short enough \
+             to open: the backtrace was left out, the report was truncated._"
+        ),
+        "{}",
+        body
+    );
+}
+
+#[test]
+fn multi_byte_characters_are_not_split() {
+    install();
+
+    // Move the multi-byte characters across the cut point one byte at a time.
+    for n in 0..64 {
+        let note = format!("{}{}", "x".repeat(n), "é日本😀".repeat(200));
+        let (url, body) = issue(eyre!("disk full").note(note));
+
+        assert!(url.len() <= MAX_LEN, "{} > {}", url.len(), MAX_LEN);
+        assert!(body.contains("0: disk full"));
+        assert!(body.contains("the report was truncated"), "{}", body);
+    }
+}
+
+#[test]
+fn error_and_metadata_are_kept_past_the_limit() {
+    install();
+
+    let message = "ü".repeat(MAX_LEN);
+    let (url, body) = issue(eyre!("{}", message));
+
+    assert!(url.len() > MAX_LEN);
+    assert!(body.contains(&format!("0: {}\n", message)));
+    assert!(body.contains("|**version**|1.2.3|"), "{}", body);
+    assert!(body.contains("the report was truncated"), "{}", body);
+}
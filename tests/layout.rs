@@ -0,0 +1,60 @@
+use color_eyre::config::{HookBuilder, ReportLayout, ReportPart};
+use color_eyre::section::{DefinedKind, SectionKind};
+use color_eyre::{eyre::eyre, Handler, Section};
+
+#[test]
+fn suggestions_above_backtrace_grouped_and_notes_hidden() {
+    HookBuilder::blank()
+        .theme(color_eyre::config::Theme::new())
+        .report_layout(
+            ReportLayout::new()
+                .priority(ReportPart::Section(SectionKind::Suggestion), 55)
+                .hide(ReportPart::Section(SectionKind::Note))
+                .group_sections(true),
+        )
+        .install()
+        .unwrap();
+
+    let report = eyre!("failed to open the config")
+        .note("the config is read from the working directory")
+        .suggestion("run `app init` first")
+        .section("custom section")
+        .suggestion("pass `--config`");
+    let output = format!("{:?}", report);
+
+    assert!(!output.contains("working directory"));
+    assert!(output.contains("Suggestions:\n   - run `app init` first\n   - pass `--config`"));
+    let custom = output.find("custom section").unwrap();
+    let suggestions = output.find("Suggestions:").unwrap();
+    assert!(suggestions > custom);
+    if let Some(backtrace) = output.find("BACKTRACE") {
+        assert!(suggestions < backtrace);
+    }
+
+    let docs = DefinedKind::new("Docs").icon("📖");
+    let report = eyre!("failed to parse the config")
+        .labeled(docs, "https://example.com/docs/config")
+        .labeled(
+            DefinedKind::new("Workaround").priority(95),
+            "delete the cache",
+        )
+        .labeled(docs, "https://example.com/docs/cache");
+    let output = format!("{:?}", report);
+
+    assert!(output.contains(
+        "📖 Docs:\n   - https://example.com/docs/config\n   - https://example.com/docs/cache"
+    ));
+    let workaround = output.find("Workaround:\n   - delete the cache").unwrap();
+    assert!(workaround < output.find("Docs:").unwrap());
+
+    let handler = report.handler().downcast_ref::<Handler>().unwrap();
+    let kinds: Vec<_> = handler.sections().map(|section| section.kind()).collect();
+    assert_eq!(
+        kinds,
+        [
+            SectionKind::Defined("Docs"),
+            SectionKind::Defined("Workaround"),
+            SectionKind::Defined("Docs"),
+        ]
+    );
+}
@@ -0,0 +1,31 @@
+use color_eyre::config::{HookBuilder, Theme};
+use color_eyre::scope::{in_panic_section, with_panic_section};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn panic_sections_of_the_current_scope() {
+    let (panic_hook, _) = HookBuilder::blank().theme(Theme::new()).into_hooks();
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    let hook_reports = reports.clone();
+    std::panic::set_hook(Box::new(move |info| {
+        let report = panic_hook.panic_report(info).to_string();
+        hook_reports.lock().unwrap().push(report);
+    }));
+
+    let _ = std::thread::spawn(|| {
+        let _job = with_panic_section("job", 42);
+        {
+            let _file = with_panic_section("file", "done.csv");
+        }
+        in_panic_section("file", "rows.csv", || panic!("invalid row"));
+    })
+    .join();
+
+    let _ = std::thread::spawn(|| panic!("no sections")).join();
+    let _ = std::panic::take_hook();
+
+    let reports = reports.lock().unwrap();
+    assert!(reports[0].contains("Context:\n    job: 42\n   file: rows.csv"));
+    assert!(!reports[0].contains("done.csv"));
+    assert!(!reports[1].contains("Context:"));
+}
@@ -0,0 +1,82 @@
+#![cfg(feature = "raw-backtrace")]
+use color_eyre::symbolize::RawBacktrace;
+
+const RAW: &str = "\
+color-eyre raw backtrace v1
+m0 8f3c1a2b /usr/bin/my-app
+m1 - /usr/lib/libfoo.so
+0 m0+0x1a2b3c
+1 m1+0x40 foo::bar
+2 0x7f3e9c1d2e3f __libc_start_main";
+
+#[test]
+fn display_round_trips_through_from_str() {
+    let raw: RawBacktrace = RAW.parse().unwrap();
+    assert_eq!(raw.len(), 3);
+    assert_eq!(raw.to_string(), RAW);
+
+    let reparsed: RawBacktrace = raw.to_string().parse().unwrap();
+    assert_eq!(reparsed, raw);
+}
+
+#[test]
+fn surrounding_report_is_skipped() {
+    let report = format!(
+        "Error:\n   0: something went wrong\n\n{}\n\nRun with RUST_BACKTRACE=full\n",
+        RAW.replace('\n', "\n  ")
+    );
+
+    let raw: RawBacktrace = report.parse().unwrap();
+    assert_eq!(raw, RAW.parse().unwrap());
+}
+
+#[test]
+fn missing_marker_is_rejected() {
+    let err = "0 m0+0x1a2b3c".parse::<RawBacktrace>().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "no raw backtrace found, expected a \"color-eyre raw backtrace v1\" line"
+    );
+}
+
+#[test]
+fn other_versions_are_rejected() {
+    let err = RAW
+        .replace("backtrace v1", "backtrace v2")
+        .parse::<RawBacktrace>()
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "unsupported raw backtrace version v2, only v1 is supported"
+    );
+}
+
+#[test]
+fn malformed_lines_are_rejected() {
+    let malformed = [
+        // modules must be numbered in order
+        "m1 8f3c1a2b /usr/bin/my-app",
+        // build-ids are whole bytes of hex
+        "m0 8f3 /usr/bin/my-app",
+        "m0 zz /usr/bin/my-app",
+        // modules need a path
+        "m0 8f3c1a2b",
+        // frames must refer to a known module
+        "0 m0+0x10",
+        // addresses are hex with a 0x prefix
+        "0 12345",
+        "x m0+0x10",
+        "0",
+    ];
+
+    for line in &malformed {
+        let input = format!("color-eyre raw backtrace v1\n{}", line);
+        let err = input.parse::<RawBacktrace>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("invalid raw backtrace line: {:?}", line),
+            "{}",
+            input
+        );
+    }
+}
@@ -0,0 +1,27 @@
+use color_eyre::config::{HookBuilder, Theme};
+use color_eyre::eyre::eyre;
+use color_eyre::scope::{in_report_section, with_report_section};
+use color_eyre::Section;
+
+#[test]
+fn reports_snapshot_the_sections_of_the_current_scope() {
+    HookBuilder::blank().theme(Theme::new()).install().unwrap();
+
+    let _request = with_report_section("request_id", "4f2a9c");
+    let report =
+        in_report_section("attempt", 2, || eyre!("failed to load the user")).field("user", "admin");
+    let output = format!("{:?}", report);
+    assert!(
+        output.contains("Context:\n   request_id: 4f2a9c\n      attempt: 2\n         user: admin")
+    );
+
+    // The scope ended, but the report keeps its snapshot.
+    let output = format!("{:?}", eyre!("failed again"));
+    assert!(output.contains("request_id: 4f2a9c"));
+    assert!(!output.contains("attempt"));
+
+    let output = std::thread::spawn(|| format!("{:?}", eyre!("on another thread")))
+        .join()
+        .unwrap();
+    assert!(!output.contains("Context:"));
+}
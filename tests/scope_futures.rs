@@ -0,0 +1,89 @@
+use color_eyre::config::{HookBuilder, Theme};
+use color_eyre::eyre::eyre;
+use color_eyre::scope::{in_panic_section_async, in_report_section_async};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+
+struct NoopWaker;
+
+impl Wake for NoopWaker {
+    fn wake(self: Arc<Self>) {}
+}
+
+/// A future that creates a report on each poll, and panics on the third
+struct Steps {
+    reports: Arc<Mutex<Vec<String>>>,
+    polls: usize,
+}
+
+impl Future for Steps {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<()> {
+        self.polls += 1;
+        if self.polls == 3 {
+            panic!("invalid row");
+        }
+
+        let report = format!("{:?}", eyre!("poll {}", self.polls));
+        self.reports.lock().unwrap().push(report);
+        Poll::Pending
+    }
+}
+
+/// Poll `future` once on a new thread and hand it back
+fn poll_on_thread<F>(mut future: Pin<Box<F>>) -> std::thread::Result<Pin<Box<F>>>
+where
+    F: Future + Send + 'static,
+{
+    std::thread::spawn(move || {
+        let waker = Waker::from(Arc::new(NoopWaker));
+        let _ = future.as_mut().poll(&mut Context::from_waker(&waker));
+        future
+    })
+    .join()
+}
+
+#[test]
+fn futures_keep_their_sections_across_threads() {
+    let (panic_hook, eyre_hook) = HookBuilder::blank().theme(Theme::new()).into_hooks();
+    eyre_hook.install().unwrap();
+    let panics = Arc::new(Mutex::new(Vec::new()));
+    let hook_panics = panics.clone();
+    std::panic::set_hook(Box::new(move |info| {
+        let report = panic_hook.panic_report(info).to_string();
+        hook_panics.lock().unwrap().push(report);
+    }));
+
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    let steps = Steps {
+        reports: reports.clone(),
+        polls: 0,
+    };
+    let future = Box::pin(in_panic_section_async(
+        "job",
+        42,
+        in_report_section_async("request_id", "4f2a9c", steps),
+    ));
+
+    let future = poll_on_thread(future).unwrap();
+    let future = poll_on_thread(future).unwrap();
+    assert!(poll_on_thread(future).is_err());
+    let _ = std::panic::take_hook();
+
+    let reports = reports.lock().unwrap();
+    assert_eq!(reports.len(), 2);
+    for report in reports.iter() {
+        assert!(report.contains("request_id: 4f2a9c"), "{}", report);
+        assert!(!report.contains("job"), "{}", report);
+    }
+
+    let panics = panics.lock().unwrap();
+    assert!(panics[0].contains("job: 42"), "{}", panics[0]);
+
+    // The sections are only active while the future is polled.
+    let output = format!("{:?}", eyre!("outside the future"));
+    assert!(!output.contains("request_id"));
+}
@@ -0,0 +1,68 @@
+use color_eyre::eyre::{eyre, Report};
+use color_eyre::section::{FieldValue, SectionKind};
+use color_eyre::{Handler, Section};
+
+#[derive(Debug, thiserror::Error)]
+#[error("row {0} is invalid")]
+struct InvalidRow(usize);
+
+fn handler(report: &Report) -> &Handler {
+    report.handler().downcast_ref::<Handler>().unwrap()
+}
+
+#[test]
+fn read_remove_and_replace_sections() {
+    color_eyre::install().unwrap();
+
+    let mut report = eyre!("failed to import the file")
+        .error(InvalidRow(3))
+        .note("the file has 10 rows")
+        .suggestion("fix the rows and try again");
+
+    let sections: Vec<_> = handler(&report)
+        .sections()
+        .map(|section| (section.kind(), section.to_string()))
+        .collect();
+    assert_eq!(
+        sections,
+        [
+            (SectionKind::Error, "row 3 is invalid".to_string()),
+            (SectionKind::Note, "the file has 10 rows".to_string()),
+            (
+                SectionKind::Suggestion,
+                "fix the rows and try again".to_string()
+            ),
+        ]
+    );
+
+    let error = handler(&report)
+        .sections()
+        .find_map(|section| section.error());
+    assert_eq!(error.unwrap().downcast_ref::<InvalidRow>().unwrap().0, 3);
+
+    let handler_mut = report.handler_mut().downcast_mut::<Handler>().unwrap();
+    handler_mut.retain_sections(|section| section.kind() != SectionKind::Suggestion);
+    handler_mut.replace_section(1, "the file has 12 rows");
+
+    let output = format!("{:?}", report);
+    assert!(!output.contains("fix the rows"));
+    assert!(output.contains("the file has 12 rows"));
+    assert!(output.contains("row 3 is invalid"));
+
+    let report = eyre!("request failed")
+        .field("request_id", "4f2a9c")
+        .field("retry", 3);
+
+    let fields: Vec<_> = handler(&report)
+        .sections()
+        .filter_map(|section| section.field())
+        .collect();
+    assert_eq!(
+        fields,
+        [
+            ("request_id", &FieldValue::String("4f2a9c".into())),
+            ("retry", &FieldValue::Int(3)),
+        ]
+    );
+    assert!(format!("{:?}", report).contains("Context:\n   request_id: 4f2a9c\n        retry: 3"));
+}
@@ -0,0 +1,121 @@
+#![cfg(feature = "capture-spantrace")]
+use color_eyre::config::{HookBuilder, SpanFieldAction, Theme};
+use color_eyre::eyre::{self, eyre};
+use tracing_subscriber::prelude::*;
+
+#[tracing::instrument]
+fn connect(host: &str) -> eyre::Report {
+    let _login = tracing::info_span!(
+        "login",
+        user = "admin",
+        password = "hunter2",
+        api_token = "abc123",
+        tokens = 3,
+        tok = "kept",
+        query = %"a=b c=d, e=f",
+        body = "0123456789",
+        quoted = "say \"hi\" now",
+        config = ?Config { retries: 3, mode: "fast" },
+        note = 42,
+    )
+    .entered();
+
+    eyre!("failed to connect")
+}
+
+#[derive(Debug)]
+#[allow(dead_code)]
+struct Config {
+    retries: u32,
+    mode: &'static str,
+}
+
+fn report() -> eyre::Report {
+    let subscriber = tracing_subscriber::registry().with(tracing_error::ErrorLayer::default());
+    tracing::subscriber::with_default(subscriber, || {
+        let _outer = tracing::info_span!("outer", id = 1).entered();
+        connect("db.internal")
+    })
+}
+
+/// The body of the last crash report sent
+#[cfg(feature = "crash-report")]
+static CRASH_REPORT: std::sync::Mutex<String> = std::sync::Mutex::new(String::new());
+
+#[cfg(feature = "crash-report")]
+struct Endpoint;
+
+#[cfg(feature = "crash-report")]
+impl color_eyre::crash_report::Transport for Endpoint {
+    fn send(&self, _url: &str, body: &str) -> std::io::Result<()> {
+        *CRASH_REPORT.lock().unwrap() = body.to_string();
+        Ok(())
+    }
+}
+
+#[test]
+fn rules_and_limit_are_applied() {
+    std::env::set_var("RUST_LIB_BACKTRACE", "0");
+
+    let hook = HookBuilder::default();
+
+    #[cfg(feature = "crash-report")]
+    let hook = hook.crash_reporter(color_eyre::crash_report::CrashReporter::with_transport(
+        "http://crashes.invalid/report",
+        Endpoint,
+    ));
+
+    hook.theme(Theme::new())
+        .span_field_rule("password", SpanFieldAction::Drop)
+        .span_field_rule("*token*", SpanFieldAction::Redact)
+        .span_field_rule("body", SpanFieldAction::Truncate(4))
+        .span_field_rule("quoted", SpanFieldAction::Truncate(5))
+        .span_field_rule("q*y", SpanFieldAction::Truncate(100))
+        .span_limit(2)
+        .install()
+        .unwrap();
+
+    let report = report();
+
+    #[cfg(feature = "crash-report")]
+    {
+        color_eyre::crash_report::send(&report).unwrap();
+        let body = CRASH_REPORT.lock().unwrap();
+        let spans = &body[body.find(r#""spans":["#).unwrap()..];
+        assert!(spans.contains(r#"{"target":"span_filter","name":"login","fields":"#));
+        assert!(spans.contains(r#"api_token=[redacted]"#), "{}", spans);
+        assert!(spans.contains(r#"body=\"0123…\""#), "{}", spans);
+        assert!(!spans.contains("password"));
+        assert!(spans.contains(r#""name":"connect""#));
+        assert!(!spans.contains(r#""name":"outer""#));
+    }
+
+    let report = format!("{:?}", report);
+    let login = report
+        .lines()
+        .find(|line| line.contains("span_filter::login"))
+        .unwrap();
+
+    // Drop
+    assert!(!login.contains("password"));
+    assert!(!report.contains("hunter2"));
+    // Redact, with `*` matching nothing and a suffix
+    assert!(login.contains(r#"api_token=[redacted]"#), "{}", login);
+    assert!(login.contains("tokens=[redacted]"), "{}", login);
+    assert!(login.contains(r#"tok="kept""#), "{}", login);
+    // Truncate keeps the quotes, even when cutting next to an escape
+    assert!(login.contains(r#"body="0123…""#), "{}", login);
+    assert!(login.contains(r#"quoted="say …""#), "{}", login);
+    // Values with `=`, `, ` and brackets stay whole
+    assert!(login.contains("query=a=b c=d, e=f body="), "{}", login);
+    assert!(
+        login.contains(r#"config=Config { retries: 3, mode: "fast" } note=42"#),
+        "{}",
+        login
+    );
+
+    // Limit: `login` and `connect` are printed, `outer` is not.
+    assert!(report.contains("span_filter::connect with host=\"db.internal\""));
+    assert!(!report.contains("span_filter::outer"));
+    assert!(report.contains("1 span hidden"));
+}
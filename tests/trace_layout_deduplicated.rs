@@ -0,0 +1,72 @@
+#![cfg(feature = "capture-spantrace")]
+use color_eyre::config::{HookBuilder, Theme, TraceLayout};
+use color_eyre::eyre::{self, eyre};
+use tracing_subscriber::prelude::*;
+
+#[tracing::instrument]
+fn outer(n: u32) -> eyre::Report {
+    inner()
+}
+
+#[tracing::instrument]
+fn inner() -> eyre::Report {
+    eyre!("failed to connect")
+}
+
+#[test]
+fn spans_are_folded_into_their_frames() {
+    std::env::set_var("RUST_LIB_BACKTRACE", "1");
+
+    HookBuilder::default()
+        .theme(Theme::new())
+        .trace_layout(TraceLayout::Deduplicated)
+        .install()
+        .unwrap();
+
+    let subscriber = tracing_subscriber::registry().with(tracing_error::ErrorLayer::default());
+    let report = tracing::subscriber::with_default(subscriber, || {
+        let _request = tracing::info_span!("request", id = 7).entered();
+        format!("{:?}", outer(3))
+    });
+
+    assert!(!report.contains("SPANTRACE"));
+    let start = report.find("EXECUTION TRACE").unwrap();
+    let lines: Vec<_> = report[start..]
+        .lines()
+        .skip(1)
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("at "))
+        .take_while(|line| !line.starts_with("Run with"))
+        .collect();
+
+    assert!(!lines
+        .iter()
+        .any(|line| line.contains("⋄ trace_layout_deduplicated::inner")));
+    assert!(!lines
+        .iter()
+        .any(|line| line.contains("⋄ trace_layout_deduplicated::outer")));
+    assert!(lines
+        .iter()
+        .any(|line| line.contains(": trace_layout_deduplicated::outer")
+            && line.ends_with(" with n=3")));
+    assert!(lines
+        .iter()
+        .any(|line| line.starts_with("⋄ trace_layout_deduplicated::request with id=7")));
+
+    // Every frame is either printed or counted as hidden, including the ones
+    // without a matching span.
+    let mut next = 1;
+    for line in &lines {
+        if let Some(hidden) = line.strip_prefix("⋮ ") {
+            next += hidden.split(' ').next().unwrap().parse::<usize>().unwrap();
+        } else if let Some((n, _)) = line.split_once(": ") {
+            if let Ok(n) = n.parse::<usize>() {
+                assert_eq!(n, next, "{:#?}", lines);
+                next += 1;
+            }
+        }
+    }
+    assert!(lines.iter().any(
+        |line| line.contains(": trace_layout_deduplicated::spans_are_folded_into_their_frames")
+    ));
+}
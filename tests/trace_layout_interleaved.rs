@@ -0,0 +1,68 @@
+#![cfg(feature = "capture-spantrace")]
+use color_eyre::config::{HookBuilder, Theme, TraceLayout};
+use color_eyre::eyre::{self, eyre};
+use tracing_subscriber::prelude::*;
+
+#[tracing::instrument]
+fn outer(n: u32) -> eyre::Report {
+    inner()
+}
+
+#[tracing::instrument]
+fn inner() -> eyre::Report {
+    eyre!("failed to connect")
+}
+
+fn is_frame(line: &str, function: &str) -> bool {
+    line.split_once(": ")
+        .is_some_and(|(n, name)| n.parse::<usize>().is_ok() && name.starts_with(function))
+}
+
+#[test]
+fn spans_are_placed_before_their_frames() {
+    std::env::set_var("RUST_LIB_BACKTRACE", "1");
+
+    HookBuilder::default()
+        .theme(Theme::new())
+        .trace_layout(TraceLayout::Interleaved)
+        .install()
+        .unwrap();
+
+    let subscriber = tracing_subscriber::registry().with(tracing_error::ErrorLayer::default());
+    let report = tracing::subscriber::with_default(subscriber, || {
+        let _request = tracing::info_span!("request", id = 7).entered();
+        format!("{:?}", outer(3))
+    });
+
+    assert!(!report.contains("SPANTRACE"));
+    let start = report.find("EXECUTION TRACE").unwrap();
+    let lines: Vec<_> = report[start..]
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("at "))
+        .collect();
+    let position = |prefix: &str| {
+        lines
+            .iter()
+            .position(|line| line.starts_with(prefix))
+            .unwrap_or_else(|| panic!("{:?} not found in {:#?}", prefix, lines))
+    };
+
+    let inner_span = position("⋄ trace_layout_interleaved::inner");
+    let outer_span = position("⋄ trace_layout_interleaved::outer with n=3");
+    assert!(inner_span < outer_span);
+    assert!(
+        is_frame(lines[inner_span + 1], "trace_layout_interleaved::inner"),
+        "{:#?}",
+        lines
+    );
+    assert!(
+        is_frame(lines[outer_span + 1], "trace_layout_interleaved::outer"),
+        "{:#?}",
+        lines
+    );
+
+    // No frame of a function named `request` entered the `request` span, so
+    // it's listed after the last matched span.
+    assert!(outer_span < position("⋄ trace_layout_interleaved::request with id=7"));
+}
@@ -0,0 +1,34 @@
+#![cfg(feature = "capture-spantrace")]
+use color_eyre::config::{HookBuilder, Theme, TraceLayout};
+use color_eyre::eyre::{self, eyre};
+use tracing_subscriber::prelude::*;
+
+#[tracing::instrument]
+fn outer(n: u32) -> eyre::Report {
+    eyre!("failed to connect")
+}
+
+#[test]
+fn span_trace_and_backtrace_are_separate_sections() {
+    std::env::set_var("RUST_LIB_BACKTRACE", "1");
+
+    HookBuilder::default()
+        .theme(Theme::new())
+        .trace_layout(TraceLayout::Separate)
+        .install()
+        .unwrap();
+
+    let subscriber = tracing_subscriber::registry().with(tracing_error::ErrorLayer::default());
+    let report = tracing::subscriber::with_default(subscriber, || format!("{:?}", outer(3)));
+
+    assert!(!report.contains("EXECUTION TRACE"));
+    assert!(!report.contains('⋄'));
+
+    let span_trace = report.find("SPANTRACE").unwrap();
+    let backtrace = report.find(" BACKTRACE ").unwrap();
+    let span = report
+        .find("trace_layout_separate::outer with n=3")
+        .unwrap();
+    assert!(span_trace < span && span < backtrace, "{}", report);
+    assert!(report[backtrace..].contains(": trace_layout_separate::outer"));
+}